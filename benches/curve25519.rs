@@ -1,42 +1,94 @@
-use criterion::{criterion_group, criterion_main, Criterion, Fun};
-use curve25519::{curve25519, curve25519_sk};
-
-fn curve25519_bench_no_rand() {
-    let random: [u8; 32] = [
-        0x77, 0x07, 0x6a, 0x0a, 0x73, 0x18, 0xa5, 0x7d, 0x3c, 0x16, 0xc1, 0x72,
-        0x51, 0xb2, 0x66, 0x45, 0xdf, 0x4c, 0x2f, 0x87, 0xeb, 0xc0, 0x99, 0x2a,
-        0xb1, 0x77, 0xfb, 0xa5, 0x1d, 0xb9, 0x2c, 0x2a,
-    ];
-    let sk = curve25519_sk(Some(random)).unwrap();
-    let mut basepoint: [u8; 32] = [0; 32];
-    basepoint[0] = 9;
-    let pk = basepoint;
-    let _ = curve25519(sk, pk);
-}
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use curve25519::{
+    curve25519, curve25519_pk, curve25519_sk,
+    hazmat::{ge_scalarmult_base, ge_scalarmult_ct, GeP2, GeP3},
+    x25519_base, x25519_many, FieldElement,
+};
 
-fn curve25519_bench_rand() {
-    let sk = curve25519_sk(None).unwrap();
+const SCALAR: [u8; 32] = [
+    0x77, 0x07, 0x6a, 0x0a, 0x73, 0x18, 0xa5, 0x7d, 0x3c, 0x16, 0xc1, 0x72,
+    0x51, 0xb2, 0x66, 0x45, 0xdf, 0x4c, 0x2f, 0x87, 0xeb, 0xc0, 0x99, 0x2a,
+    0xb1, 0x77, 0xfb, 0xa5, 0x1d, 0xb9, 0x2c, 0x0a,
+];
+
+fn x25519_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("x25519");
     let mut basepoint: [u8; 32] = [0; 32];
     basepoint[0] = 9;
-    let pk = basepoint;
-    let _ = curve25519(sk, pk);
-}
 
-fn criterion_benchmark(c: &mut Criterion) {
-    let curve25519_no_rand = Fun::new("curve25519_bench_no_rand", |b, _| {
-        b.iter(curve25519_bench_no_rand)
+    group.bench_function("curve25519", |b| {
+        let sk = curve25519_sk(Some(SCALAR)).unwrap();
+        b.iter(|| curve25519(black_box(sk), black_box(basepoint)))
+    });
+    group.bench_function("curve25519_rand", |b| {
+        b.iter(|| curve25519(curve25519_sk(None).unwrap(), basepoint))
+    });
+    group.bench_function("curve25519_pk", |b| {
+        b.iter(|| curve25519_pk(black_box(SCALAR)))
+    });
+    group.bench_function("x25519_base", |b| {
+        b.iter(|| x25519_base(black_box(SCALAR)))
+    });
+
+    let publics: Vec<[u8; 32]> = (0..16u8)
+        .map(|i| curve25519_pk(curve25519_sk(Some([i; 32])).unwrap()))
+        .collect();
+    group.bench_function("curve25519_16", |b| {
+        b.iter(|| {
+            publics
+                .iter()
+                .map(|pk| curve25519(black_box(SCALAR), *pk))
+                .collect::<Vec<_>>()
+        })
     });
+    group.bench_function("x25519_many_16", |b| {
+        let mut out = [[0u8; 32]; 16];
+        b.iter(|| x25519_many(black_box(SCALAR), &publics, &mut out))
+    });
+
+    group.finish();
+}
 
-    let curve25519_rand = Fun::new("curve25519_bench_rand", |b, _| {
-        b.iter(curve25519_bench_rand)
+fn edwards_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("edwards");
+
+    group.bench_function("ge_scalarmult_base", |b| {
+        b.iter(|| ge_scalarmult_base(black_box(&SCALAR)))
+    });
+    group.bench_function("ge_scalarmult_ct", |b| {
+        let point = ge_scalarmult_base(&SCALAR);
+        b.iter(|| ge_scalarmult_ct(black_box(&SCALAR), black_box(&point)))
     });
+    group.bench_function("double_scalarmult_vartime", |b| {
+        let point = ge_scalarmult_base(&SCALAR).to_bytes();
+        let point = GeP3::from_bytes_vartime(&point).unwrap();
+        b.iter(|| {
+            GeP2::double_scalarmult_vartime(
+                black_box(&SCALAR),
+                point,
+                black_box(&SCALAR),
+            )
+        })
+    });
+
+    group.finish();
+}
+
+fn field_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("field");
+    let x = FieldElement::from_u64(0x1234_5678_9abc_def0);
+    let y = x.invert();
+
+    group.bench_function("mul", |b| b.iter(|| black_box(x) * black_box(y)));
+    group.bench_function("invert", |b| b.iter(|| black_box(x).invert()));
 
-    c.bench_functions(
-        "curve25519",
-        vec![curve25519_no_rand, curve25519_rand],
-        &0,
-    );
+    group.finish();
 }
 
-criterion_group!(benches, criterion_benchmark);
+criterion_group!(
+    benches,
+    x25519_benchmarks,
+    edwards_benchmarks,
+    field_benchmarks
+);
 criterion_main!(benches);
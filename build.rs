@@ -1,8 +1,11 @@
-fn main() -> Result<(), ()> {
+fn main() {
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-changed=./src/util_helpers.h");
+    // The C/ASM helpers are only needed by the opt-in `asm` feature, the
+    // default build is pure Rust and doesn't even pull in `cc`.
+    // `portable` overrides `asm`, see src/util.rs.
+    #[cfg(all(feature = "asm", not(feature = "portable")))]
     cc::Build::new()
         .file("./src/util_helpers.c")
         .compile("util_helpers");
-    Ok(())
 }
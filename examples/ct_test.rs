@@ -0,0 +1,88 @@
+//! Collects timings of `curve25519` and `ge_scalarmult_base` for a fixed
+//! scalar versus random scalars, see the `ct_test` module documentation.
+//!
+//!     cargo run --release --features ct-test --example ct_test > timings.csv
+//!
+//! The number of samples is the optional argument, 10000 by default. The
+//! raw `class,cycles` lines go to stdout, the t statistics to stderr.
+//!
+//! The `ct_test` module only exists on x86_64 without the `portable`
+//! feature; elsewhere this example builds but only says so.
+
+#[cfg(all(target_arch = "x86_64", not(feature = "portable")))]
+mod imp {
+    use curve25519::{
+        ct_test::{measure, welch_t},
+        curve25519,
+        hazmat::ge_scalarmult_base,
+    };
+    use std::env;
+
+    pub fn main() {
+        let samples = env::args()
+            .nth(1)
+            .map(|n| n.parse().expect("samples must be a number"))
+            .unwrap_or(10_000);
+
+        // xorshift, reproducible and cheap; the inputs don't need to be
+        // secret.
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        // Class 0 is one fixed scalar, class 1 fresh random ones, in random
+        // order.
+        let fixed = [0x55u8; 32];
+        let inputs: Vec<(usize, [u8; 32])> = (0..samples)
+            .map(|_| {
+                let class = (next() & 1) as usize;
+                let mut scalar = fixed;
+                if class == 1 {
+                    for b in scalar.iter_mut() {
+                        *b = next() as u8;
+                    }
+                }
+                scalar[31] &= 127;
+                (class, scalar)
+            })
+            .collect();
+
+        let mut basepoint = [0u8; 32];
+        basepoint[0] = 9;
+        report(
+            "curve25519",
+            &inputs,
+            &measure(&inputs, |(_, k)| {
+                curve25519(*k, basepoint);
+            }),
+        );
+        report(
+            "ge_scalarmult_base",
+            &inputs,
+            &measure(&inputs, |(_, k)| {
+                ge_scalarmult_base(k);
+            }),
+        );
+    }
+
+    fn report(name: &str, inputs: &[(usize, [u8; 32])], timings: &[u64]) {
+        let mut classes = [Vec::new(), Vec::new()];
+        for (&(class, _), &t) in inputs.iter().zip(timings) {
+            println!("{},{},{}", name, class, t);
+            classes[class].push(t);
+        }
+        eprintln!("{}: t = {:.2}", name, welch_t(&classes[0], &classes[1]));
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "portable")))]
+fn main() { imp::main() }
+
+#[cfg(not(all(target_arch = "x86_64", not(feature = "portable"))))]
+fn main() {
+    eprintln!("ct_test needs x86_64 and a build without `portable`");
+}
@@ -0,0 +1,226 @@
+//! Compressed points on the Edwards form of curve25519.
+
+use crate::{
+    hazmat::GeP3,
+    util::{from_hex, impl_hex_fmt},
+    Error, FieldElement,
+};
+
+/// A point encoded as its y-coordinate plus the sign of x, the 32-byte
+/// format of `GeP3::to_bytes` and of RFC 8032.
+///
+/// The wrapper only holds bytes; they aren't checked to be a point until
+/// [`decompress`](CompressedEdwardsY::decompress).
+///
+/// Formats as 64 hex digits with `{}`, `{:x}` and `{:X}`. Compares and
+/// hashes as its bytes, so two encodings of the same point are different
+/// keys.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CompressedEdwardsY([u8; 32]);
+
+impl_hex_fmt!(CompressedEdwardsY);
+
+impl CompressedEdwardsY {
+    /// Decodes the point, returning `None` if the bytes aren't the encoding
+    /// of a curve point.
+    ///
+    /// This is **not** constant time, only use it with public points.
+    pub fn decompress(&self) -> Option<GeP3> {
+        GeP3::from_bytes_vartime(&self.0)
+    }
+
+    /// Like [`decompress`](Self::decompress), but also returns `None` for
+    /// the eight points of small order: the identity, the point of order 2,
+    /// the two of order 4 (the all-zero encoding is one of them) and the
+    /// four of order 8.
+    ///
+    /// Use it to vet a public key once, e.g. at registration: a
+    /// small-order key makes every signature or shared secret under it
+    /// predictable. Points of large order with a small-order component
+    /// are still accepted; they are the encodings of points outside the
+    /// prime-order subgroup and are harmless for cofactored checks.
+    ///
+    /// This is **not** constant time, only use it with public points.
+    pub fn decompress_rejecting_small_order(&self) -> Option<GeP3> {
+        let point = self.decompress()?;
+        if point.is_small_order() {
+            return None;
+        }
+        Some(point)
+    }
+
+    /// A cheap structural check: whether the y-coordinate, with the sign
+    /// bit masked off, is below p.
+    ///
+    /// This is **not** point validation. A `true` only means the bytes
+    /// aren't malformed; about half of the y-coordinates that pass have no
+    /// matching x and still fail [`decompress`](Self::decompress), which
+    /// needs a square root to tell. Use it to drop garbage early, and
+    /// decompress to actually accept a point.
+    pub fn is_valid(&self) -> bool {
+        let mut y = self.0;
+        y[31] &= 0x7f;
+        FieldElement::is_canonical_bytes(&y)
+    }
+
+    /// Parse 64 hex digits, in either case, e.g. the output of `Display`.
+    ///
+    /// Like the byte conversions this doesn't check the point; fails only
+    /// with `Error::InvalidLength` or `Error::InvalidEncoding` when the
+    /// string isn't 64 hex digits.
+    pub fn from_hex(s: &str) -> Result<CompressedEdwardsY, Error> {
+        from_hex(s).map(CompressedEdwardsY)
+    }
+
+    /// A reference to the encoded bytes.
+    pub fn as_bytes(&self) -> &[u8; 32] { &self.0 }
+
+    /// The encoded bytes.
+    pub fn to_bytes(&self) -> [u8; 32] { self.0 }
+}
+
+impl From<[u8; 32]> for CompressedEdwardsY {
+    fn from(bytes: [u8; 32]) -> CompressedEdwardsY { CompressedEdwardsY(bytes) }
+}
+
+impl AsRef<[u8]> for CompressedEdwardsY {
+    fn as_ref(&self) -> &[u8] { &self.0 }
+}
+
+impl Default for CompressedEdwardsY {
+    /// The encoding of the identity, `(0, 1)`.
+    fn default() -> CompressedEdwardsY {
+        let mut bytes = [0u8; 32];
+        bytes[0] = 1;
+        CompressedEdwardsY(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CompressedEdwardsY;
+    use crate::hazmat::{ge_scalarmult_base, GeP3};
+
+    #[test]
+    fn compress_decompress_round_trip() {
+        for k in 0..16u8 {
+            let mut scalar = [0u8; 32];
+            scalar[0] = k.wrapping_mul(37);
+            scalar[17] = k;
+            let bytes = ge_scalarmult_base(&scalar).to_bytes();
+
+            let compressed = CompressedEdwardsY::from(bytes);
+            assert_eq!(compressed.as_ref(), &bytes[..]);
+            let point = compressed.decompress().unwrap();
+            assert_eq!(point.to_bytes(), bytes);
+        }
+    }
+
+    #[test]
+    fn hashes_as_bytes() {
+        extern crate std;
+        use std::collections::HashSet;
+
+        let bytes = ge_scalarmult_base(&[3; 32]).to_bytes();
+        let mut seen = HashSet::new();
+        seen.insert(CompressedEdwardsY::from(bytes));
+        assert!(seen.contains(&CompressedEdwardsY::from(bytes)));
+        assert!(!seen.contains(&CompressedEdwardsY::default()));
+    }
+
+    #[test]
+    fn default_is_identity() {
+        let identity = CompressedEdwardsY::default().decompress().unwrap();
+        assert_eq!(
+            identity.to_bytes(),
+            ge_scalarmult_base(&[0; 32]).to_bytes()
+        );
+    }
+
+    #[test]
+    fn off_curve_is_rejected() {
+        // y = 2 gives x^2 = 3 / (4d + 1), which isn't a square.
+        let mut bytes = [0u8; 32];
+        bytes[0] = 2;
+        assert!(CompressedEdwardsY::from(bytes).decompress().is_none());
+        assert!(GeP3::from_bytes_vartime(&bytes).is_none());
+    }
+
+    // The canonical encodings of the eight points of order dividing 8.
+    const SMALL_ORDER: [&str; 8] = [
+        "0100000000000000000000000000000000000000000000000000000000000000",
+        "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "0000000000000000000000000000000000000000000000000000000000000080",
+        "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a",
+        "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac03fa",
+        "26e8958fc2b227b045c3f489f2ef98f0d5dfac05d3c63339b13802886d53fc05",
+        "26e8958fc2b227b045c3f489f2ef98f0d5dfac05d3c63339b13802886d53fc85",
+    ];
+
+    #[test]
+    fn small_order_points_are_rejected() {
+        for hex in SMALL_ORDER.iter() {
+            let compressed = CompressedEdwardsY::from_hex(hex).unwrap();
+            let point = compressed.decompress().unwrap();
+            assert!(point.is_small_order());
+            assert!(compressed.decompress_rejecting_small_order().is_none());
+        }
+
+        // The multiples of a point of order 8 are exactly the list.
+        let t = CompressedEdwardsY::from_hex(SMALL_ORDER[4])
+            .unwrap()
+            .decompress()
+            .unwrap();
+        let mut encodings = [[0u8; 32]; 8];
+        for (bytes, hex) in encodings.iter_mut().zip(SMALL_ORDER.iter()) {
+            *bytes = CompressedEdwardsY::from_hex(hex).unwrap().to_bytes();
+        }
+        let mut multiple = GeP3::zero();
+        for _ in 0..8 {
+            assert!(encodings.contains(&multiple.to_bytes()));
+            multiple = multiple + t;
+        }
+        assert!(multiple.is_identity());
+    }
+
+    #[test]
+    fn large_order_points_are_accepted() {
+        let b = ge_scalarmult_base(&[7; 32]);
+        let compressed = CompressedEdwardsY::from(b.to_bytes());
+        assert!(compressed.decompress_rejecting_small_order().unwrap() == b);
+
+        // Adding a small-order component doesn't make the order small.
+        let t = CompressedEdwardsY::from_hex(SMALL_ORDER[4])
+            .unwrap()
+            .decompress()
+            .unwrap();
+        let mixed = CompressedEdwardsY::from((b + t).to_bytes());
+        assert!(mixed.decompress_rejecting_small_order().is_some());
+    }
+
+    #[test]
+    fn is_valid_checks_y_only() {
+        let point = ge_scalarmult_base(&[7; 32]).to_bytes();
+        assert!(CompressedEdwardsY::from(point).is_valid());
+        assert!(CompressedEdwardsY::default().is_valid());
+
+        // y = p - 1 with either sign is well formed; y = p isn't.
+        let mut p_minus_one = [0xff; 32];
+        p_minus_one[0] = 0xec;
+        p_minus_one[31] = 0x7f;
+        assert!(CompressedEdwardsY::from(p_minus_one).is_valid());
+        p_minus_one[31] |= 0x80;
+        assert!(CompressedEdwardsY::from(p_minus_one).is_valid());
+        let mut p = p_minus_one;
+        p[0] = 0xed;
+        assert!(!CompressedEdwardsY::from(p).is_valid());
+        assert!(!CompressedEdwardsY::from([0xff; 32]).is_valid());
+
+        // Well formed, but not on the curve.
+        let mut two = [0u8; 32];
+        two[0] = 2;
+        assert!(CompressedEdwardsY::from(two).is_valid());
+        assert!(CompressedEdwardsY::from(two).decompress().is_none());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{
+    statics::{FE_MONTGOMERY_A, FE_ONE, FE_ZERO},
+    FieldElement,
+};
+
+// g(u) = u^3 + A u^2 + u, the right-hand side of the Montgomery curve
+// v^2 = u^3 + A u^2 + u.
+pub(crate) fn montgomery_rhs(u: &FieldElement) -> FieldElement {
+    *u * (u.square() + FE_MONTGOMERY_A * *u + FE_ONE)
+}
+
+fn is_square(x: &FieldElement) -> bool {
+    FieldElement::sqrt_ratio_m1(x, &FE_ONE).0
+}
+
+/// Maps a field element to the u-coordinate of a point on curve25519 with
+/// Elligator 2.
+///
+/// This is `map_to_curve_elligator2` from [RFC 9380], section 6.7.1, with
+/// `Z = 2`, the mapping used by the `curve25519_XMD:SHA-512_ELL2_RO_`
+/// suite. The suite hashes the message to two field elements, maps both
+/// with this function, adds the points and clears the cofactor; only the
+/// map itself lives here.
+///
+/// `r` and `-r` map to the same point. When `u` on the first candidate
+/// `-A / (1 + 2 r^2)` isn't on the curve the second candidate `-u - A` is,
+/// so every input yields a curve point.
+///
+/// [RFC 9380]: https://www.rfc-editor.org/rfc/rfc9380
+pub fn elligator2_map(r: &FieldElement) -> FieldElement { map_to_curve(r).0 }
+
+// elligator2_map, also reporting whether u is the first candidate. The
+// v-coordinate hash-to-curve needs is sqrt(g(u)) with the sign picked by
+// that branch, RFC 9380 section 6.7.1, steps 6 and 7.
+pub(crate) fn map_to_curve(r: &FieldElement) -> (FieldElement, bool) {
+    let mut tv1 = (*r + *r) * *r;
+    // 1 + 2 r^2 = 0 has to be special-cased, the RFC sends it to -A.
+    let exceptional = !(tv1 + FE_ONE).is_nonzero();
+    tv1.maybe_set(&FE_ZERO, i32::from(exceptional));
+
+    let x1 = FE_MONTGOMERY_A.neg() * (tv1 + FE_ONE).invert();
+    let gx1 = montgomery_rhs(&x1);
+    let x2 = x1.neg() - FE_MONTGOMERY_A;
+
+    let first = is_square(&gx1);
+    let mut x = x2;
+    x.maybe_set(&x1, i32::from(first));
+    (x, first)
+}
+
+/// The inverse of [`elligator2_map`]: finds a non-negative `r` with
+/// `elligator2_map(&r) == *u`.
+///
+/// Returns `None` when `u` isn't the u-coordinate of a curve point or has
+/// no preimage; roughly half of the curve points have one. `alt` picks
+/// between the two preimage formulas, one per branch of the map, which
+/// disagree on which points they can reach.
+pub fn elligator2_inverse(u: &FieldElement, alt: bool) -> Option<FieldElement> {
+    if !is_square(&montgomery_rhs(u)) {
+        return None;
+    }
+
+    // u = -A / (1 + 2 r^2) gives r^2 = -(u + A) / 2u, and the second
+    // branch, u = A / (1 + 2 r^2) - A, gives r^2 = -u / 2(u + A).
+    let u_plus_a = *u + FE_MONTGOMERY_A;
+    let (num, den) = if alt {
+        (u.neg(), u_plus_a + u_plus_a)
+    } else {
+        (u_plus_a.neg(), *u + *u)
+    };
+    let (was_square, r) = FieldElement::sqrt_ratio_m1(&num, &den);
+    if !was_square || !den.is_nonzero() {
+        return None;
+    }
+
+    Some(r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{elligator2_inverse, elligator2_map};
+    use crate::{util::from_hex, FieldElement};
+
+    // RFC 9380 prints field elements big-endian.
+    fn from_hex_be(s: &str) -> FieldElement {
+        let mut bytes: [u8; 32] = from_hex(s).unwrap();
+        bytes.reverse();
+        FieldElement::from_bytes(&bytes)
+    }
+
+    // RFC 9380, J.4.1, curve25519_XMD:SHA-512_ELL2_RO_: (u0, Q0.x) and
+    // (u1, Q1.x) of each message.
+    static RO_VECTORS: [(&str, &str); 10] = [
+        (
+            "49bed021c7a3748f09fa8cdfcac044089f7829d3531066ac9e74e0994e05bc7d",
+            "16b3d86e056b7970fa00165f6f48d90b619ad618791661b7b5e1ec78be10eac1",
+        ),
+        (
+            "5c36525b663e63389d886105cee7ed712325d5a97e60e140aba7e2ce5ae851b6",
+            "7ec29ddbf34539c40adfa98fcb39ec36368f47f30e8f888cc7e86f4d46e0c264",
+        ),
+        (
+            "6412b7485ba26d3d1b6c290a8e1435b2959f03721874939b21782df17323d160",
+            "71de3dadfe268872326c35ac512164850860567aea0e7325e6b91a98f86533ad",
+        ),
+        (
+            "24c7b46c1c6d9a21d32f5707be1380ab82db1054fde82865d5c9e3d968f287b2",
+            "5704069021f61e41779e2ba6b932268316d6d2a6f064f997a22fef16d1eaeaca",
+        ),
+        (
+            "5e123990f11bbb5586613ffabdb58d47f64bb5f2fa115f8ea8df0188e0c9e1b5",
+            "7a94d45a198fb5daa381f45f2619ab279744efdd8bd8ed587fc5b65d6cea1df0",
+        ),
+        (
+            "5e8553eb00438a0bb1e7faa59dec6d8087f9c8011e5fb8ed9df31cb6c0d4ac19",
+            "30506fb7a32136694abd61b6113770270debe593027a968a01f271e146e60c18",
+        ),
+        (
+            "20f481e85da7a3bf60ac0fb11ed1d0558fc6f941b3ac5469aa8b56ec883d6d7d",
+            "02d606e2699b918ee36f2818f2bc5013e437e673c9f9b9cdc15fd0c5ee913970",
+        ),
+        (
+            "017d57fd257e9a78913999a23b52ca988157a81b09c5442501d07fed20869465",
+            "38920e9b988d1ab7449c0fa9a6058192c0c797bb3d42ac345724341a1aa98745",
+        ),
+        (
+            "005fe8a7b8fef0a16c105e6cadf5a6740b3365e18692a9c05bfbb4d97f645a6a",
+            "36b4df0c864c64707cbf6cf36e9ee2c09a6cb93b28313c169be29561bb904f98",
+        ),
+        (
+            "1347edbec6a2b5d8c02e058819819bee177077c9d10a4ce165aab0fd0252261a",
+            "3fa114783a505c0b2b2fbeef0102853c0b494e7757f2a089d0daae7ed9a0db2b",
+        ),
+    ];
+
+    #[test]
+    fn rfc9380_map_to_curve() {
+        for &(r, u) in RO_VECTORS.iter() {
+            assert!(elligator2_map(&from_hex_be(r)) == from_hex_be(u));
+        }
+    }
+
+    #[test]
+    fn inverse_round_trips() {
+        for &(r, _) in RO_VECTORS.iter() {
+            let u = elligator2_map(&from_hex_be(r));
+            let mut found = false;
+            for &alt in [false, true].iter() {
+                if let Some(r) = elligator2_inverse(&u, alt) {
+                    assert!(elligator2_map(&r) == u);
+                    found = true;
+                }
+            }
+            assert!(found);
+        }
+    }
+
+    #[test]
+    fn inverse_rejects_twist() {
+        // u = 2 is on the twist: 2^3 + A 2^2 + 2 isn't a square.
+        let mut two = [0u8; 32];
+        two[0] = 2;
+        let two = FieldElement::from_bytes(&two);
+        assert!(elligator2_inverse(&two, false).is_none());
+        assert!(elligator2_inverse(&two, true).is_none());
+    }
+}
@@ -0,0 +1,44 @@
+use core::fmt;
+
+/// Errors returned by this crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The random number generator failed to produce bytes.
+    Rng,
+    /// The input doesn't have the expected number of bytes.
+    InvalidLength,
+    /// The bytes don't decode to a value of the expected type, e.g. a
+    /// point that isn't on the curve.
+    InvalidEncoding,
+    /// The bytes are not the canonical encoding of the value, e.g. a
+    /// scalar that isn't reduced modulo the group order.
+    NonCanonical,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Error::Rng => "random number generator failed",
+            Error::InvalidLength => "invalid input length",
+            Error::InvalidEncoding => "invalid encoding",
+            Error::NonCanonical => "non-canonical encoding",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::Error;
+    use std::string::ToString;
+
+    #[test]
+    fn display() {
+        assert_eq!(Error::Rng.to_string(), "random number generator failed");
+        assert_eq!(Error::InvalidLength.to_string(), "invalid input length");
+    }
+}
@@ -0,0 +1,219 @@
+//! Four-way AVX2 multiplication for the 10-limb field representation.
+//!
+//! Limb `i` of the four operands sits in the four 64-bit lanes of one
+//! register, so every `_mm256_mul_epi32` computes the same partial product
+//! for all four elements at once. The carry chain runs on the same
+//! registers.
+
+use crate::FieldElement;
+use core::arch::x86_64::{
+    __m256i, _mm256_add_epi64, _mm256_mul_epi32, _mm256_set1_epi64x,
+    _mm256_set_epi64x, _mm256_setzero_si256, _mm256_slli_epi64,
+    _mm256_sllv_epi64, _mm256_srlv_epi64, _mm256_storeu_si256,
+    _mm256_sub_epi64,
+};
+
+// AVX2 has no 64-bit arithmetic shift, so the signed carries of the scalar
+// code are taken on `h + 2^62`, which is positive for every column sum,
+// and the bias is shifted back out afterwards.
+const CARRY_BIAS: i64 = 1 << 62;
+
+// `carry = (h[i] + 2^(bits-1)) >> bits`, `h[i] -= carry << bits`; returns
+// the carry.
+#[inline(always)]
+unsafe fn carry(h: &mut [__m256i; 10], i: usize, bits: i64) -> __m256i {
+    let round = _mm256_set1_epi64x(CARRY_BIAS + (1 << (bits - 1)));
+    let unbias = _mm256_set1_epi64x(CARRY_BIAS >> bits);
+    let count = _mm256_set1_epi64x(bits);
+    let c = _mm256_srlv_epi64(_mm256_add_epi64(h[i], round), count);
+    let c = _mm256_sub_epi64(c, unbias);
+    h[i] = _mm256_sub_epi64(h[i], _mm256_sllv_epi64(c, count));
+    c
+}
+
+// Limb `i` of `v[l]` goes to lane `l` of the `i`th register.
+#[inline(always)]
+unsafe fn transpose(v: &[FieldElement; 4]) -> [__m256i; 10] {
+    let mut t = [_mm256_setzero_si256(); 10];
+    for (i, ti) in t.iter_mut().enumerate() {
+        *ti = _mm256_set_epi64x(
+            i64::from(v[3].0[i]),
+            i64::from(v[2].0[i]),
+            i64::from(v[1].0[i]),
+            i64::from(v[0].0[i]),
+        );
+    }
+    t
+}
+
+// `h + f * g` on the low 32 bits of each lane of `f` and `g`.
+#[inline(always)]
+unsafe fn mac(h: __m256i, f: __m256i, g: __m256i) -> __m256i {
+    _mm256_add_epi64(h, _mm256_mul_epi32(f, g))
+}
+
+// `h[l] = a[l] * b[l]` for the four lanes `l`.
+//
+// Same preconditions and postconditions as `Mul for FieldElement`; the
+// 2 and 19 precomputations still fit in the low 32 bits of each lane,
+// which is all `_mm256_mul_epi32` reads.
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn mul4(
+    a: &[FieldElement; 4],
+    b: &[FieldElement; 4],
+) -> [FieldElement; 4] {
+    let nineteen = _mm256_set1_epi64x(19);
+
+    // Column `k` collects f_i g_j with i + j = k, plus 19 f_i g_j with
+    // i + j = k + 10. Products of two odd limbs are doubled since those
+    // limbs only carry 25 bits.
+    let [f0, f1, f2, f3, f4, f5, f6, f7, f8, f9] = transpose(a);
+    let [g0, g1, g2, g3, g4, g5, g6, g7, g8, g9] = transpose(b);
+    let g1_19 = _mm256_mul_epi32(g1, nineteen);
+    let g2_19 = _mm256_mul_epi32(g2, nineteen);
+    let g3_19 = _mm256_mul_epi32(g3, nineteen);
+    let g4_19 = _mm256_mul_epi32(g4, nineteen);
+    let g5_19 = _mm256_mul_epi32(g5, nineteen);
+    let g6_19 = _mm256_mul_epi32(g6, nineteen);
+    let g7_19 = _mm256_mul_epi32(g7, nineteen);
+    let g8_19 = _mm256_mul_epi32(g8, nineteen);
+    let g9_19 = _mm256_mul_epi32(g9, nineteen);
+    let f1_2 = _mm256_add_epi64(f1, f1);
+    let f3_2 = _mm256_add_epi64(f3, f3);
+    let f5_2 = _mm256_add_epi64(f5, f5);
+    let f7_2 = _mm256_add_epi64(f7, f7);
+    let f9_2 = _mm256_add_epi64(f9, f9);
+
+    let mut h0 = _mm256_mul_epi32(f0, g0);
+    h0 = mac(h0, f1_2, g9_19);
+    h0 = mac(h0, f2, g8_19);
+    h0 = mac(h0, f3_2, g7_19);
+    h0 = mac(h0, f4, g6_19);
+    h0 = mac(h0, f5_2, g5_19);
+    h0 = mac(h0, f6, g4_19);
+    h0 = mac(h0, f7_2, g3_19);
+    h0 = mac(h0, f8, g2_19);
+    h0 = mac(h0, f9_2, g1_19);
+    let mut h1 = _mm256_mul_epi32(f0, g1);
+    h1 = mac(h1, f1, g0);
+    h1 = mac(h1, f2, g9_19);
+    h1 = mac(h1, f3, g8_19);
+    h1 = mac(h1, f4, g7_19);
+    h1 = mac(h1, f5, g6_19);
+    h1 = mac(h1, f6, g5_19);
+    h1 = mac(h1, f7, g4_19);
+    h1 = mac(h1, f8, g3_19);
+    h1 = mac(h1, f9, g2_19);
+    let mut h2 = _mm256_mul_epi32(f0, g2);
+    h2 = mac(h2, f1_2, g1);
+    h2 = mac(h2, f2, g0);
+    h2 = mac(h2, f3_2, g9_19);
+    h2 = mac(h2, f4, g8_19);
+    h2 = mac(h2, f5_2, g7_19);
+    h2 = mac(h2, f6, g6_19);
+    h2 = mac(h2, f7_2, g5_19);
+    h2 = mac(h2, f8, g4_19);
+    h2 = mac(h2, f9_2, g3_19);
+    let mut h3 = _mm256_mul_epi32(f0, g3);
+    h3 = mac(h3, f1, g2);
+    h3 = mac(h3, f2, g1);
+    h3 = mac(h3, f3, g0);
+    h3 = mac(h3, f4, g9_19);
+    h3 = mac(h3, f5, g8_19);
+    h3 = mac(h3, f6, g7_19);
+    h3 = mac(h3, f7, g6_19);
+    h3 = mac(h3, f8, g5_19);
+    h3 = mac(h3, f9, g4_19);
+    let mut h4 = _mm256_mul_epi32(f0, g4);
+    h4 = mac(h4, f1_2, g3);
+    h4 = mac(h4, f2, g2);
+    h4 = mac(h4, f3_2, g1);
+    h4 = mac(h4, f4, g0);
+    h4 = mac(h4, f5_2, g9_19);
+    h4 = mac(h4, f6, g8_19);
+    h4 = mac(h4, f7_2, g7_19);
+    h4 = mac(h4, f8, g6_19);
+    h4 = mac(h4, f9_2, g5_19);
+    let mut h5 = _mm256_mul_epi32(f0, g5);
+    h5 = mac(h5, f1, g4);
+    h5 = mac(h5, f2, g3);
+    h5 = mac(h5, f3, g2);
+    h5 = mac(h5, f4, g1);
+    h5 = mac(h5, f5, g0);
+    h5 = mac(h5, f6, g9_19);
+    h5 = mac(h5, f7, g8_19);
+    h5 = mac(h5, f8, g7_19);
+    h5 = mac(h5, f9, g6_19);
+    let mut h6 = _mm256_mul_epi32(f0, g6);
+    h6 = mac(h6, f1_2, g5);
+    h6 = mac(h6, f2, g4);
+    h6 = mac(h6, f3_2, g3);
+    h6 = mac(h6, f4, g2);
+    h6 = mac(h6, f5_2, g1);
+    h6 = mac(h6, f6, g0);
+    h6 = mac(h6, f7_2, g9_19);
+    h6 = mac(h6, f8, g8_19);
+    h6 = mac(h6, f9_2, g7_19);
+    let mut h7 = _mm256_mul_epi32(f0, g7);
+    h7 = mac(h7, f1, g6);
+    h7 = mac(h7, f2, g5);
+    h7 = mac(h7, f3, g4);
+    h7 = mac(h7, f4, g3);
+    h7 = mac(h7, f5, g2);
+    h7 = mac(h7, f6, g1);
+    h7 = mac(h7, f7, g0);
+    h7 = mac(h7, f8, g9_19);
+    h7 = mac(h7, f9, g8_19);
+    let mut h8 = _mm256_mul_epi32(f0, g8);
+    h8 = mac(h8, f1_2, g7);
+    h8 = mac(h8, f2, g6);
+    h8 = mac(h8, f3_2, g5);
+    h8 = mac(h8, f4, g4);
+    h8 = mac(h8, f5_2, g3);
+    h8 = mac(h8, f6, g2);
+    h8 = mac(h8, f7_2, g1);
+    h8 = mac(h8, f8, g0);
+    h8 = mac(h8, f9_2, g9_19);
+    let mut h9 = _mm256_mul_epi32(f0, g9);
+    h9 = mac(h9, f1, g8);
+    h9 = mac(h9, f2, g7);
+    h9 = mac(h9, f3, g6);
+    h9 = mac(h9, f4, g5);
+    h9 = mac(h9, f5, g4);
+    h9 = mac(h9, f6, g3);
+    h9 = mac(h9, f7, g2);
+    h9 = mac(h9, f8, g1);
+    h9 = mac(h9, f9, g0);
+
+    let mut h = [h0, h1, h2, h3, h4, h5, h6, h7, h8, h9];
+
+    // Same carry order as `Mul for FieldElement`.
+    let chain = [(0, 26), (4, 26), (1, 25), (5, 25), (2, 26), (6, 26)];
+    let chain = chain.iter().chain(&[(3, 25), (7, 25), (4, 26), (8, 26)]);
+    for &(i, bits) in chain {
+        let c = carry(&mut h, i, bits);
+        h[i + 1] = _mm256_add_epi64(h[i + 1], c);
+    }
+    // carry9 is too wide for `_mm256_mul_epi32`, so 19 = 16 + 2 + 1.
+    let c = carry(&mut h, 9, 25);
+    let c_19 = _mm256_add_epi64(
+        _mm256_add_epi64(_mm256_slli_epi64(c, 4), _mm256_slli_epi64(c, 1)),
+        c,
+    );
+    h[0] = _mm256_add_epi64(h[0], c_19);
+    let c = carry(&mut h, 0, 26);
+    h[1] = _mm256_add_epi64(h[1], c);
+
+    let mut limbs = [[0i64; 4]; 10];
+    for (limb, hk) in limbs.iter_mut().zip(h.iter()) {
+        _mm256_storeu_si256(limb.as_mut_ptr() as *mut __m256i, *hk);
+    }
+
+    let mut out = [FieldElement([0; 10]); 4];
+    for (l, fe) in out.iter_mut().enumerate() {
+        for (t, limb) in fe.0.iter_mut().zip(limbs.iter()) {
+            *t = limb[l] as i32;
+        }
+    }
+    out
+}
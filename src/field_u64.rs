@@ -0,0 +1,418 @@
+use core::ops::{Add, Mul, Sub};
+
+/// Here the field is \Z/(2^255-19).
+///
+/// An element t, entries t\[0\]...t\[4\], represents the integer
+/// `t[0]+2^51 t[1]+2^102 t[2]+2^153 t[3]+2^204 t[4]`.
+/// Bounds on each t\[i\] vary depending on context, a reduced element has
+/// every t\[i\] below 2^51 plus a small carry.
+#[derive(Clone, Copy)]
+pub struct FieldElement(pub(crate) [u64; 5]);
+
+const LOW_51_BIT_MASK: u64 = (1 << 51) - 1;
+
+// 16 * p, spread over the limbs. Adding it before a subtraction or a
+// negation keeps every limb positive as long as the subtrahend's limbs are
+// below 2^55.
+const P_TIMES_16_LO: u64 = 36_028_797_018_963_664; // 16 * (2^51 - 19)
+const P_TIMES_16_HI: u64 = 36_028_797_018_963_952; // 16 * (2^51 - 1)
+
+#[inline(always)]
+fn m(x: u64, y: u64) -> u128 { u128::from(x) * u128::from(y) }
+
+#[inline]
+fn load_8u(s: &[u8]) -> u64 {
+    u64::from(s[0])
+        | (u64::from(s[1]) << 8)
+        | (u64::from(s[2]) << 16)
+        | (u64::from(s[3]) << 24)
+        | (u64::from(s[4]) << 32)
+        | (u64::from(s[5]) << 40)
+        | (u64::from(s[6]) << 48)
+        | (u64::from(s[7]) << 56)
+}
+
+impl Add for FieldElement {
+    type Output = FieldElement;
+
+    // `h = f + g`
+    // Can overlap `h` with `f` or `g`.
+    //
+    // No carries, so `|h|` grows by one bit; `Mul` accepts limbs up to 2^54.
+    fn add(self, rhs: FieldElement) -> FieldElement {
+        let FieldElement(f) = self;
+        let FieldElement(g) = rhs;
+        FieldElement([
+            f[0] + g[0],
+            f[1] + g[1],
+            f[2] + g[2],
+            f[3] + g[3],
+            f[4] + g[4],
+        ])
+    }
+}
+
+impl Sub for FieldElement {
+    type Output = FieldElement;
+
+    // `h = f - g`
+    // Can overlap `h` with `f` or `g`.
+    //
+    // Preconditions:
+    //    |g| limbs bounded by 16 * (2^51 - 19), the lowest limb of 16 p;
+    //    anything larger can underflow. Reduced inputs (< 2^52) are fine.
+    //
+    // Postconditions:
+    //    |h| reduced.
+    fn sub(self, rhs: FieldElement) -> FieldElement {
+        let FieldElement(f) = self;
+        let FieldElement(g) = rhs;
+        FieldElement::reduce([
+            (f[0] + P_TIMES_16_LO) - g[0],
+            (f[1] + P_TIMES_16_HI) - g[1],
+            (f[2] + P_TIMES_16_HI) - g[2],
+            (f[3] + P_TIMES_16_HI) - g[3],
+            (f[4] + P_TIMES_16_HI) - g[4],
+        ])
+    }
+}
+
+impl Mul for FieldElement {
+    type Output = FieldElement;
+
+    // `h = f * g`
+    // Can overlap h with `f` or `g`.
+    //
+    // Preconditions:
+    //    |f|, |g| limbs bounded by 2^54.
+    //
+    // Postconditions:
+    //    |h| reduced.
+    //
+    // Schoolbook multiplication with 128-bit products. Since 2^255 = 19 mod
+    // p, the products landing above 2^255 are folded back in by
+    // precomputing `19 * g[i]` (below 2^59, so products stay below 2^113
+    // and each column sum below 2^115).
+    fn mul(self, rhs: FieldElement) -> FieldElement {
+        let FieldElement(f) = self;
+        let FieldElement(g) = rhs;
+
+        let g1_19 = g[1] * 19;
+        let g2_19 = g[2] * 19;
+        let g3_19 = g[3] * 19;
+        let g4_19 = g[4] * 19;
+
+        let c0 = m(f[0], g[0])
+            + m(f[4], g1_19)
+            + m(f[3], g2_19)
+            + m(f[2], g3_19)
+            + m(f[1], g4_19);
+        let c1 = m(f[1], g[0])
+            + m(f[0], g[1])
+            + m(f[4], g2_19)
+            + m(f[3], g3_19)
+            + m(f[2], g4_19);
+        let c2 = m(f[2], g[0])
+            + m(f[1], g[1])
+            + m(f[0], g[2])
+            + m(f[4], g3_19)
+            + m(f[3], g4_19);
+        let c3 = m(f[3], g[0])
+            + m(f[2], g[1])
+            + m(f[1], g[2])
+            + m(f[0], g[3])
+            + m(f[4], g4_19);
+        let c4 = m(f[4], g[0])
+            + m(f[3], g[1])
+            + m(f[2], g[2])
+            + m(f[1], g[3])
+            + m(f[0], g[4]);
+
+        FieldElement::carry_wide([c0, c1, c2, c3, c4])
+    }
+}
+
+impl FieldElement {
+    /// Builds an element from the 10 limbs of the 32-bit backend,
+    /// `t[0]+2^26 t[1]+2^51 t[2]+...+2^230 t[9]`. The curve constants are
+    /// written this way for every backend.
+    ///
+    /// The limbs are taken as they are; use
+    /// [`from_limbs_checked`](FieldElement::from_limbs_checked) to catch
+    /// out-of-bounds limbs.
+    // Pairs of 26/25-bit limbs line up with one 51-bit limb each; 16p is
+    // added before carrying so that negative limbs come out positive.
+    pub const fn from_limbs(t: [i32; 10]) -> FieldElement {
+        let mut h = [0i64; 5];
+        let mut i = 0;
+        while i < 5 {
+            h[i] = (t[2 * i] as i64) + ((t[2 * i + 1] as i64) << 26);
+            i += 1;
+        }
+        h[0] += P_TIMES_16_LO as i64;
+        h[1] += P_TIMES_16_HI as i64;
+        h[2] += P_TIMES_16_HI as i64;
+        h[3] += P_TIMES_16_HI as i64;
+        h[4] += P_TIMES_16_HI as i64;
+
+        let mask = LOW_51_BIT_MASK as i64;
+        let mut carry;
+        carry = h[0] >> 51;
+        h[1] += carry;
+        h[0] &= mask;
+        carry = h[1] >> 51;
+        h[2] += carry;
+        h[1] &= mask;
+        carry = h[2] >> 51;
+        h[3] += carry;
+        h[2] &= mask;
+        carry = h[3] >> 51;
+        h[4] += carry;
+        h[3] &= mask;
+        carry = h[4] >> 51;
+        h[0] += carry * 19;
+        h[4] &= mask;
+
+        FieldElement([
+            h[0] as u64,
+            h[1] as u64,
+            h[2] as u64,
+            h[3] as u64,
+            h[4] as u64,
+        ])
+    }
+
+    // One carry pass over 64-bit limbs, leaving each limb below 2^51 plus
+    // a small carry into t[0] and t[1].
+    #[inline]
+    fn reduce(mut h: [u64; 5]) -> FieldElement {
+        let c0 = h[0] >> 51;
+        let c1 = h[1] >> 51;
+        let c2 = h[2] >> 51;
+        let c3 = h[3] >> 51;
+        let c4 = h[4] >> 51;
+
+        h[0] &= LOW_51_BIT_MASK;
+        h[1] &= LOW_51_BIT_MASK;
+        h[2] &= LOW_51_BIT_MASK;
+        h[3] &= LOW_51_BIT_MASK;
+        h[4] &= LOW_51_BIT_MASK;
+
+        h[0] += c4 * 19;
+        h[1] += c0;
+        h[2] += c1;
+        h[3] += c2;
+        h[4] += c3;
+
+        FieldElement(h)
+    }
+
+    // Carry 128-bit column sums (each below 2^115) down to reduced limbs.
+    #[inline(always)]
+    fn carry_wide(c: [u128; 5]) -> FieldElement {
+        let [c0, mut c1, mut c2, mut c3, mut c4] = c;
+
+        c1 += u128::from((c0 >> 51) as u64);
+        let mut h0 = (c0 as u64) & LOW_51_BIT_MASK;
+        c2 += u128::from((c1 >> 51) as u64);
+        let mut h1 = (c1 as u64) & LOW_51_BIT_MASK;
+        c3 += u128::from((c2 >> 51) as u64);
+        let h2 = (c2 as u64) & LOW_51_BIT_MASK;
+        c4 += u128::from((c3 >> 51) as u64);
+        let h3 = (c3 as u64) & LOW_51_BIT_MASK;
+        let carry = (c4 >> 51) as u64;
+        let h4 = (c4 as u64) & LOW_51_BIT_MASK;
+
+        // c4 has no factors of 19 in it, so carry < 2^60 and carry * 19
+        // still fits.
+        h0 += carry * 19;
+        h1 += h0 >> 51;
+        h0 &= LOW_51_BIT_MASK;
+
+        FieldElement([h0, h1, h2, h3, h4])
+    }
+
+    /// The field element `x`, in constant time.
+    ///
+    /// `x` fits in the two lowest limbs and is already below p, so there's
+    /// nothing to carry or reduce.
+    pub const fn from_u64(x: u64) -> FieldElement {
+        FieldElement([x & LOW_51_BIT_MASK, x >> 51, 0, 0, 0])
+    }
+
+    /// Decodes a little-endian integer, ignoring the top bit of `s[31]`.
+    ///
+    /// Values in `[p, 2^255)` aren't reduced here, they stay as they are
+    /// until the arithmetic or `to_bytes` reduces them modulo p. Use
+    /// `from_bytes_wide_reduce` to reduce every bit of a wider input.
+    pub fn from_bytes(s: &[u8; 32]) -> FieldElement {
+        FieldElement([
+            load_8u(&s[0..8]) & LOW_51_BIT_MASK,
+            (load_8u(&s[6..14]) >> 3) & LOW_51_BIT_MASK,
+            (load_8u(&s[12..20]) >> 6) & LOW_51_BIT_MASK,
+            (load_8u(&s[19..27]) >> 1) & LOW_51_BIT_MASK,
+            // Drops bit 255.
+            (load_8u(&s[24..32]) >> 12) & LOW_51_BIT_MASK,
+        ])
+    }
+
+    // Write p=2^255-19; q=floor(h/p).
+    // Same strategy as the 32-bit backend: after a reduce pass h < 2p, so
+    // q is the carry out of h + 19, and h - pq is obtained by adding 19q
+    // and dropping bit 255.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let FieldElement(mut h) = FieldElement::reduce(self.0);
+
+        let mut q = (h[0] + 19) >> 51;
+        q = (h[1] + q) >> 51;
+        q = (h[2] + q) >> 51;
+        q = (h[3] + q) >> 51;
+        q = (h[4] + q) >> 51;
+
+        h[0] += 19 * q;
+
+        h[1] += h[0] >> 51;
+        h[0] &= LOW_51_BIT_MASK;
+        h[2] += h[1] >> 51;
+        h[1] &= LOW_51_BIT_MASK;
+        h[3] += h[2] >> 51;
+        h[2] &= LOW_51_BIT_MASK;
+        h[4] += h[3] >> 51;
+        h[3] &= LOW_51_BIT_MASK;
+        // Drop the carry out of bit 255, that's the 2^255 q.
+        h[4] &= LOW_51_BIT_MASK;
+
+        let mut s = [0u8; 32];
+        let mut acc: u128 = 0;
+        let mut acc_bits = 0;
+        let mut i = 0;
+        for &limb in h.iter() {
+            acc |= u128::from(limb) << acc_bits;
+            acc_bits += 51;
+            while acc_bits >= 8 {
+                s[i] = acc as u8;
+                acc >>= 8;
+                acc_bits -= 8;
+                i += 1;
+            }
+        }
+        // 255 bits: 31 full bytes and 7 bits left over.
+        s[31] = acc as u8;
+        s
+    }
+
+    // Swaps self and other if do_swap is 1, leaves both alone if it is 0.
+    //
+    // do_swap is turned into an all-ones or all-zeros mask, any other value
+    // would mix the limbs of the two elements, so only its low bit is used.
+    // Debug builds panic on anything but 0 or 1.
+    //
+    // The original ref10-style name; conditional_swap is the same with a
+    // u8 flag.
+    pub fn maybe_swap_with(&mut self, other: &mut FieldElement, do_swap: i32) {
+        debug_assert!(do_swap == 0 || do_swap == 1);
+        let mask = (-i64::from(do_swap & 1)) as u64;
+        for (f, g) in self.0.iter_mut().zip(other.0.iter_mut()) {
+            let x = (*f ^ *g) & mask;
+            *f ^= x;
+            *g ^= x;
+        }
+    }
+
+    // Sets self to other if do_swap is 1, same mask as maybe_swap_with.
+    // The original name of conditional_assign.
+    pub fn maybe_set(&mut self, other: &FieldElement, do_swap: i32) {
+        debug_assert!(do_swap == 0 || do_swap == 1);
+        let mask = (-i64::from(do_swap & 1)) as u64;
+        for (f, g) in self.0.iter_mut().zip(other.0.iter()) {
+            *f ^= (*f ^ *g) & mask;
+        }
+    }
+
+    /// `h = f * k`, for a small constant `k` such as `121666`, cheaper than a
+    /// full multiplication. `h` is reduced.
+    ///
+    /// # Preconditions
+    ///
+    /// The limbs of `f` are bounded by `2^54` and `k < 2^20`.
+    ///
+    /// # Panics
+    ///
+    /// Debug builds panic if `k >= 2^20`; release builds don't check.
+    #[must_use]
+    pub fn mul_small(&self, k: u32) -> FieldElement {
+        debug_assert!(k < 1 << 20);
+        let FieldElement(f) = *self;
+        let k = u64::from(k);
+        FieldElement::carry_wide([
+            m(f[0], k),
+            m(f[1], k),
+            m(f[2], k),
+            m(f[3], k),
+            m(f[4], k),
+        ])
+    }
+
+    /// The same element with its limbs carried back into the bounds that
+    /// `Mul`, `square` and `to_bytes` expect.
+    ///
+    /// `Add` doesn't carry, so a sum of a few elements is fine as a factor,
+    /// but a long chain of additions, roughly eight or more, can be out of
+    /// bounds for `Mul`. Call this on such a value before multiplying.
+    /// Every limb value is accepted.
+    #[must_use]
+    pub fn reduced(&self) -> FieldElement { FieldElement::reduce(self.0) }
+
+    // h = f * 121666, the a24 constant of the Montgomery ladder.
+    pub(crate) fn mul_121666(&self) -> FieldElement { self.mul_small(121_666) }
+
+    // h = f * f
+    // Can overlap h with f.
+    //
+    // Preconditions:
+    //    |f| limbs bounded by 2^54.
+    //
+    // Postconditions:
+    //    |h| reduced.
+    #[must_use]
+    pub(crate) fn square(&self) -> FieldElement {
+        let FieldElement(f) = *self;
+
+        let f3_19 = f[3] * 19;
+        let f4_19 = f[4] * 19;
+
+        let c0 = m(f[0], f[0]) + 2 * (m(f[1], f4_19) + m(f[2], f3_19));
+        let c1 = m(f[3], f3_19) + 2 * (m(f[0], f[1]) + m(f[2], f4_19));
+        let c2 = m(f[1], f[1]) + 2 * (m(f[0], f[2]) + m(f[4], f3_19));
+        let c3 = m(f[4], f4_19) + 2 * (m(f[0], f[3]) + m(f[1], f[2]));
+        let c4 = m(f[2], f[2]) + 2 * (m(f[0], f[4]) + m(f[1], f[3]));
+
+        FieldElement::carry_wide([c0, c1, c2, c3, c4])
+    }
+
+    // h = 2 * f * f
+    // Can overlap h with f.
+    //
+    // Preconditions:
+    //    |f| limbs bounded by 2^54.
+    //
+    // Postconditions:
+    //    |h| limbs bounded by 2^52.
+    #[must_use]
+    pub(crate) fn square_and_double(&self) -> FieldElement {
+        let h = self.square();
+        h + h
+    }
+
+    #[must_use]
+    pub(crate) fn neg(&self) -> FieldElement {
+        let FieldElement(f) = *self;
+        FieldElement::reduce([
+            P_TIMES_16_LO - f[0],
+            P_TIMES_16_HI - f[1],
+            P_TIMES_16_HI - f[2],
+            P_TIMES_16_HI - f[3],
+            P_TIMES_16_HI - f[4],
+        ])
+    }
+}
@@ -0,0 +1,101 @@
+//! Entry points for fuzzers, behind the `fuzzing` feature.
+//!
+//! Each function takes arbitrary bytes, so a `cargo fuzz` target is a one
+//! line wrapper, and panics only when it finds a bug: an inconsistency
+//! between code paths that should agree.
+
+use crate::hazmat::GeP3;
+
+/// Decodes the first 32 bytes of `input` as a point, re-encodes it and
+/// checks that the decoders agree with each other. Returns whether the
+/// bytes were a valid encoding; `false` as well if `input` is shorter than
+/// 32 bytes.
+///
+/// Checks that:
+/// - the variable-time and constant-time decoders accept the same inputs and
+///   return the same point;
+/// - re-encoding a decoded point and decoding that again is stable;
+/// - the strict decoder accepts exactly the inputs that re-encode to
+///   themselves, i.e. the canonical encodings.
+///
+/// # Panics
+///
+/// Panics if any of these fail.
+pub fn fuzz_decompress_roundtrip(input: &[u8]) -> bool {
+    if input.len() < 32 {
+        return false;
+    }
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&input[..32]);
+
+    let vartime = GeP3::from_bytes_negate_vartime(&bytes);
+    let ct = GeP3::from_bytes_negate_ct(&bytes);
+    let strict = GeP3::from_bytes_strict(&bytes);
+    let negated = match (vartime, ct) {
+        (Some(vartime), Some(ct)) => {
+            assert!(vartime == ct, "decoders disagree on the point");
+            vartime
+        },
+        (None, None) => {
+            assert!(strict.is_none(), "strict decoder accepts invalid input");
+            return false;
+        },
+        _ => panic!("decoders disagree on validity"),
+    };
+
+    let point = negated.neg();
+    let encoded = point.to_bytes();
+    let decoded = GeP3::from_bytes_vartime(&encoded)
+        .expect("re-encoded point doesn't decode");
+    assert!(
+        decoded == point,
+        "re-encoded point decodes to another point"
+    );
+    assert_eq!(decoded.to_bytes(), encoded, "encoding isn't stable");
+
+    let canonical = encoded == bytes;
+    assert_eq!(strict.is_some(), canonical, "strict decoder is off");
+    if let Some(strict) = strict {
+        assert!(strict == point, "strict decoder returns another point");
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzz_decompress_roundtrip;
+    use crate::hazmat::ge_scalarmult_base;
+
+    #[test]
+    fn decompress_roundtrip_inputs() {
+        assert!(!fuzz_decompress_roundtrip(&[]));
+        assert!(!fuzz_decompress_roundtrip(&[1; 31]));
+
+        let point = ge_scalarmult_base(&[9; 32]).to_bytes();
+        assert!(fuzz_decompress_roundtrip(&point));
+        let mut longer = [0u8; 40];
+        longer[..32].copy_from_slice(&point);
+        assert!(fuzz_decompress_roundtrip(&longer));
+
+        // y = 2 isn't on the curve.
+        let mut off_curve = [0u8; 32];
+        off_curve[0] = 2;
+        assert!(!fuzz_decompress_roundtrip(&off_curve));
+
+        // y = p + 1, a non-canonical encoding of the identity, and the
+        // identity with the sign bit set, i.e. "x = -0".
+        let mut p_plus_one = [0xff; 32];
+        p_plus_one[0] = 0xee;
+        p_plus_one[31] = 0x7f;
+        assert!(fuzz_decompress_roundtrip(&p_plus_one));
+        let mut minus_zero = [0u8; 32];
+        minus_zero[0] = 1;
+        minus_zero[31] = 0x80;
+        assert!(fuzz_decompress_roundtrip(&minus_zero));
+
+        for b in 0..=255u8 {
+            fuzz_decompress_roundtrip(&[b; 32]);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! Hashing to edwards25519 with the `edwards25519_XMD:SHA-512_ELL2_RO_`
+//! suite of [RFC 9380].
+//!
+//! The output is uniformly distributed in the prime-order subgroup and has
+//! no known discrete logarithm with respect to the base point, which is
+//! what OPRFs, PAKEs and credential schemes need from a hash to the curve.
+//!
+//! [RFC 9380]: https://www.rfc-editor.org/rfc/rfc9380
+
+use crate::{
+    elligator::{map_to_curve, montgomery_rhs},
+    hazmat::GeP3,
+    statics::{FE_ONE, FE_SQRT_MINUS_A_MINUS_2, FE_ZERO},
+    FieldElement,
+};
+use sha2::{Digest, Sha512};
+
+// Two field elements of L = 48 bytes each, RFC 9380 section 8.5.
+const LEN_IN_BYTES: usize = 96;
+
+// expand_message_xmd with SHA-512, RFC 9380 section 5.3.1, producing the
+// LEN_IN_BYTES bytes hash_to_field needs, i.e. two blocks. A DST longer
+// than 255 bytes is first hashed down as section 5.3.3 prescribes.
+fn expand_message_xmd(msg: &[u8], dst: &[u8]) -> [u8; LEN_IN_BYTES] {
+    let hashed_dst;
+    let dst = if dst.len() > 255 {
+        hashed_dst = Sha512::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(dst)
+            .finalize();
+        &hashed_dst[..]
+    } else {
+        dst
+    };
+    let dst_len = [dst.len() as u8];
+
+    let b0 = Sha512::new()
+        .chain_update([0u8; 128])
+        .chain_update(msg)
+        .chain_update([0, LEN_IN_BYTES as u8, 0])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+    let b1 = Sha512::new()
+        .chain_update(b0)
+        .chain_update([1])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+    let mut b0_xor_b1 = [0u8; 64];
+    for (x, (a, b)) in b0_xor_b1.iter_mut().zip(b0.iter().zip(b1.iter())) {
+        *x = a ^ b;
+    }
+    let b2 = Sha512::new()
+        .chain_update(b0_xor_b1)
+        .chain_update([2])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+
+    let mut out = [0u8; LEN_IN_BYTES];
+    out[..64].copy_from_slice(&b1);
+    out[64..].copy_from_slice(&b2[..LEN_IN_BYTES - 64]);
+    out
+}
+
+// hash_to_field, RFC 9380 section 5.2: each 48-byte chunk is a big-endian
+// integer reduced modulo p.
+fn hash_to_field(msg: &[u8], dst: &[u8]) -> [FieldElement; 2] {
+    let uniform = expand_message_xmd(msg, dst);
+    let mut u = [FE_ZERO; 2];
+    for (u, chunk) in u.iter_mut().zip(uniform.chunks(48)) {
+        let mut wide = [0u8; 64];
+        for (w, &b) in wide.iter_mut().zip(chunk.iter().rev()) {
+            *w = b;
+        }
+        *u = FieldElement::from_bytes_wide_reduce(&wide);
+    }
+    u
+}
+
+// map_to_curve_elligator2_edwards25519, RFC 9380 section 6.8.2: Elligator 2
+// onto curve25519, then the rational map
+//     (x, y) = (sqrt(-486664) u / v, (u - 1) / (u + 1)).
+// The points with v = 0 or u = -1 go to the identity.
+fn map_to_edwards(r: &FieldElement) -> GeP3 {
+    let (u, first) = map_to_curve(r);
+    // sgn0(v) is 1 on the first branch and 0 on the second.
+    let (_, mut v) = FieldElement::sqrt_ratio_m1(&montgomery_rhs(&u), &FE_ONE);
+    v.conditional_negate(i32::from(first));
+
+    let c1_u = FE_SQRT_MINUS_A_MINUS_2 * u;
+    let u_plus_one = u + FE_ONE;
+    let u_minus_one = u - FE_ONE;
+    let mut p = GeP3 {
+        x: c1_u * u_plus_one,
+        y: u_minus_one * v,
+        z: v * u_plus_one,
+        t: c1_u * u_minus_one,
+    };
+
+    let exceptional = i32::from(!p.z.is_nonzero());
+    p.x.maybe_set(&FE_ZERO, exceptional);
+    p.y.maybe_set(&FE_ONE, exceptional);
+    p.z.maybe_set(&FE_ONE, exceptional);
+    p.t.maybe_set(&FE_ZERO, exceptional);
+    p
+}
+
+/// Hashes `msg` to a point of the prime-order subgroup of edwards25519.
+///
+/// This is `hash_to_curve` of the `edwards25519_XMD:SHA-512_ELL2_RO_`
+/// suite: `msg` is expanded to two field elements, each is mapped to the
+/// curve with Elligator 2, and the sum of the two points is multiplied by
+/// the cofactor 8.
+///
+/// `dst` is the domain separation tag, which should be unique to the
+/// protocol and ciphersuite using it; RFC 9380, section 3.1 suggests a
+/// format. Tags longer than 255 bytes are hashed down first.
+///
+/// Runs in constant time with respect to `msg`, so the input may be
+/// secret.
+pub fn hash_to_edwards(msg: &[u8], dst: &[u8]) -> GeP3 {
+    let [u0, u1] = hash_to_field(msg, dst);
+    let r = map_to_edwards(&u0) + map_to_edwards(&u1);
+    r.double_repeatedly(3)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::{hash_to_edwards, hash_to_field};
+    use crate::{util::from_hex, FieldElement};
+    use std::string::String;
+
+    const DST: &[u8] = b"QUUX-V01-CS02-with-edwards25519_XMD:SHA-512_ELL2_RO_";
+
+    // RFC 9380 prints field elements big-endian.
+    fn from_hex_be(s: &str) -> FieldElement {
+        let mut bytes: [u8; 32] = from_hex(s).unwrap();
+        bytes.reverse();
+        FieldElement::from_bytes(&bytes)
+    }
+
+    // RFC 9380, J.5.1: (msg, u0, u1, P.x, P.y).
+    fn vectors() -> [(String, [&'static str; 4]); 5] {
+        [
+            (
+                String::new(),
+                [
+                    "03fef4813c8cb5f98c6eef88fae174e6e7d5380de2b007799ac7ee712d203f3a",
+                    "780bdddd137290c8f589dc687795aafae35f6b674668d92bf92ae793e6a60c75",
+                    "3c3da6925a3c3c268448dcabb47ccde5439559d9599646a8260e47b1e4822fc6",
+                    "09a6c8561a0b22bef63124c588ce4c62ea83a3c899763af26d795302e115dc21",
+                ],
+            ),
+            (
+                String::from("abc"),
+                [
+                    "5081955c4141e4e7d02ec0e36becffaa1934df4d7a270f70679c78f9bd57c227",
+                    "005bdc17a9b378b6272573a31b04361f21c371b256252ae5463119aa0b925b76",
+                    "608040b42285cc0d72cbb3985c6b04c935370c7361f4b7fbdb1ae7f8c1a8ecad",
+                    "1a8395b88338f22e435bbd301183e7f20a5f9de643f11882fb237f88268a5531",
+                ],
+            ),
+            (
+                String::from("abcdef0123456789"),
+                [
+                    "285ebaa3be701b79871bcb6e225ecc9b0b32dff2d60424b4c50642636a78d5b3",
+                    "2e253e6a0ef658fedb8e4bd6a62d1544fd6547922acb3598ec6b369760b81b31",
+                    "6d7fabf47a2dc03fe7d47f7dddd21082c5fb8f86743cd020f3fb147d57161472",
+                    "53060a3d140e7fbcda641ed3cf42c88a75411e648a1add71217f70ea8ec561a6",
+                ],
+            ),
+            (
+                String::from("q128_") + &"q".repeat(128),
+                [
+                    "4fedd25431c41f2a606952e2945ef5e3ac905a42cf64b8b4d4a83c533bf321af",
+                    "02f20716a5801b843987097a8276b6d869295b2e11253751ca72c109d37485a9",
+                    "5fb0b92acedd16f3bcb0ef83f5c7b7a9466b5f1e0d8d217421878ea3686f8524",
+                    "2eca15e355fcfa39d2982f67ddb0eea138e2994f5956ed37b7f72eea5e89d2f7",
+                ],
+            ),
+            (
+                String::from("a512_") + &"a".repeat(512),
+                [
+                    "6e34e04a5106e9bd59f64aba49601bf09d23b27f7b594e56d5de06df4a4ea33b",
+                    "1c1c2cb59fc053f44b86c5d5eb8c1954b64976d0302d3729ff66e84068f5fd96",
+                    "0efcfde5898a839b00997fbe40d2ebe950bc81181afbd5cd6b9618aa336c1e8c",
+                    "6dc2fc04f266c5c27f236a80b14f92ccd051ef1ff027f26a07f8c0f327d8f995",
+                ],
+            ),
+        ]
+    }
+
+    #[test]
+    fn rfc9380_hash_to_field() {
+        for (msg, [u0, u1, _, _]) in vectors().iter() {
+            let u = hash_to_field(msg.as_bytes(), DST);
+            assert!(u[0] == from_hex_be(u0));
+            assert!(u[1] == from_hex_be(u1));
+        }
+    }
+
+    #[test]
+    fn rfc9380_hash_to_curve() {
+        for (msg, [_, _, x, y]) in vectors().iter() {
+            let (px, py) = hash_to_edwards(msg.as_bytes(), DST).to_affine();
+            assert!(px == from_hex_be(x));
+            assert!(py == from_hex_be(y));
+        }
+    }
+
+    #[test]
+    fn oversize_dst_is_hashed() {
+        use sha2::{Digest, Sha512};
+
+        let dst = [0x5a; 256];
+        let hashed = Sha512::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(&dst[..])
+            .finalize();
+        assert_eq!(
+            hash_to_edwards(b"msg", &dst).to_bytes(),
+            hash_to_edwards(b"msg", &hashed).to_bytes()
+        );
+    }
+}
@@ -0,0 +1,1881 @@
+//! Low-level point and scalar arithmetic.
+//!
+//! These are the ref10 building blocks the rest of the crate is written
+//! in: the point representations of the Edwards form of curve25519, the
+//! fixed-base and variable-base scalar multiplications, and the scalar
+//! arithmetic modulo the group order.
+//!
+//! Here be dragons. Nothing in this module checks its preconditions,
+//! which functions run in constant time is only written down in their
+//! comments, and the API may change in any release. Prefer the top-level
+//! functions and the `edwards` and `ristretto` modules unless you know you
+//! need these.
+
+use crate::{
+    clamp_scalar, load_3i, load_4i,
+    statics::{BI, FE_D, FE_D2, FE_ONE, FE_SQRTM1, FE_ZERO, GE_PRECOMP_BASE},
+    util::{fixed_time_eq, zeroize},
+    FieldElement,
+};
+use core::{
+    cmp::{min, Eq, PartialEq},
+    fmt,
+    ops::{Add, Sub},
+    slice,
+};
+
+/// A point in projective coordinates `(X : Y : Z)`, with `x = X/Z` and
+/// `y = Y/Z`.
+#[derive(Clone, Copy)]
+pub struct GeP2 {
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement,
+}
+
+/// A point in extended coordinates `(X : Y : Z : T)`, with `x = X/Z`,
+/// `y = Y/Z` and `x y = T/Z`.
+#[derive(Clone, Copy)]
+pub struct GeP3 {
+    pub(crate) x: FieldElement,
+    pub(crate) y: FieldElement,
+    pub(crate) z: FieldElement,
+    pub(crate) t: FieldElement,
+}
+
+/// A point in completed coordinates `((X : Z), (Y : T))`, with `x = X/Z`
+/// and `y = Y/T`. Additions and doublings produce these.
+#[derive(Clone, Copy)]
+pub struct GeP1P1 {
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement,
+    t: FieldElement,
+}
+
+/// A point cached as `(y + x, y - x, 2 d x y)`, the form of the fixed-base
+/// tables.
+#[derive(Clone, Copy)]
+pub struct GePrecomp {
+    pub(crate) y_plus_x: FieldElement,
+    pub(crate) y_minus_x: FieldElement,
+    pub(crate) xy2d: FieldElement,
+}
+
+/// A point cached as `(Y + X, Y - X, Z, 2 d T)`, ready to be added to a
+/// `GeP3`.
+///
+/// The default is all zeros, which isn't a point; it's there so tables of
+/// secret multiples can be zeroized.
+#[derive(Clone, Copy, Default)]
+pub struct GeCached {
+    pub(crate) y_plus_x: FieldElement,
+    pub(crate) y_minus_x: FieldElement,
+    pub(crate) z: FieldElement,
+    pub(crate) t2d: FieldElement,
+}
+
+impl GeP1P1 {
+    fn to_p2(&self) -> GeP2 {
+        GeP2 {
+            x: self.x * self.t,
+            y: self.y * self.z,
+            z: self.z * self.t,
+        }
+    }
+
+    pub(crate) fn to_p3(&self) -> GeP3 {
+        let [x, y, z, t] = FieldElement::mul4(
+            [self.x, self.y, self.z, self.x],
+            [self.t, self.z, self.t, self.y],
+        );
+        GeP3 { x, y, z, t }
+    }
+}
+
+// The largest digit of `GeP2::slide`: digits index the tables of odd
+// multiples P, 3P, ..., 15P (`odd_multiples`, `BI`).
+const SLIDE_MAX_DIGIT: i8 = 15;
+
+// How many bits past a nonzero digit `GeP2::slide` tries to fold into it.
+const SLIDE_LOOKAHEAD: usize = 6;
+
+impl GeP2 {
+    fn zero() -> GeP2 {
+        GeP2 {
+            x: FE_ZERO,
+            y: FE_ONE,
+            z: FE_ONE,
+        }
+    }
+
+    /// The affine coordinates `(x, y) = (X/Z, Y/Z)`, with a single
+    /// inversion.
+    pub fn to_affine(&self) -> (FieldElement, FieldElement) {
+        let recip = self.z.invert();
+        (self.x * recip, self.y * recip)
+    }
+
+    /// The RFC 8032 encoding: `y` with the sign of `x` in the top bit.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let (x, y) = self.to_affine();
+        let mut bs = y.to_bytes();
+        bs[31] ^= (if x.is_negative() { 1 } else { 0 }) << 7;
+        bs
+    }
+
+    /// Like `to_bytes`, but writes the encoding into `out`, e.g. straight
+    /// into a larger frame.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `out` is exactly 32 bytes long.
+    pub fn encode_into(&self, out: &mut [u8]) {
+        assert_eq!(out.len(), 32, "encode_into: out must be 32 bytes");
+        out.copy_from_slice(&self.to_bytes());
+    }
+
+    fn dbl(&self) -> GeP1P1 {
+        let xx = self.x.square();
+        let yy = self.y.square();
+        let b = self.z.square_and_double();
+        let a = self.x + self.y;
+        let aa = a.square();
+        let y3 = yy + xx;
+        let z3 = yy - xx;
+        let x3 = aa - y3;
+        let t3 = b - z3;
+
+        GeP1P1 {
+            x: x3,
+            y: y3,
+            z: z3,
+            t: t3,
+        }
+    }
+
+    // Writes a = a[0]+256*a[1]+...+256^31 a[31] as
+    // r[0]+2*r[1]+...+2^255 r[255] with every nonzero r[i] odd and between
+    // -SLIDE_MAX_DIGIT and SLIDE_MAX_DIGIT, looking at most SLIDE_LOOKAHEAD
+    // bits past each nonzero digit.
+    //
+    // A subtraction carries into the first zero digit above it. Digits
+    // above i are still plain bits or zeros left by earlier digits, and
+    // r[255] is zero while a < 2^255, so the carry stops at bit 255 at the
+    // latest and the sum stays exact.
+    //
+    // Preconditions:
+    //   a[31] <= 127
+    pub(crate) fn slide(a: &[u8]) -> [i8; 256] {
+        debug_assert!(a[31] <= 127);
+        let mut r = [0i8; 256];
+        for i in 0..256 {
+            r[i] = (1 & (a[i >> 3] >> (i & 7))) as i8;
+        }
+        for i in 0..256 {
+            if r[i] != 0 {
+                for b in 1..min(SLIDE_LOOKAHEAD + 1, 256 - i) {
+                    if r[i + b] != 0 {
+                        if r[i] + (r[i + b] << b) <= SLIDE_MAX_DIGIT {
+                            r[i] += r[i + b] << b;
+                            r[i + b] = 0;
+                        } else if r[i] - (r[i + b] << b) >= -SLIDE_MAX_DIGIT {
+                            r[i] -= r[i + b] << b;
+                            for k in r.iter_mut().skip(i + b) {
+                                if *k == 0 {
+                                    *k = 1;
+                                    break;
+                                }
+                                *k = 0;
+                            }
+                        } else {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        r
+    }
+
+    /// `r = a * A + b * B`, where `a` and `b` are little-endian and `B` is the
+    /// Ed25519 base point `(x, 4/5)` with `x` positive.
+    ///
+    /// This is **not** constant time, only use it with public scalars.
+    ///
+    /// # Preconditions
+    ///
+    /// `a[31] <= 127` and `b[31] <= 127`.
+    pub fn double_scalarmult_vartime(
+        a_scalar: &[u8],
+        a_point: GeP3,
+        b_scalar: &[u8],
+    ) -> GeP2 {
+        GeP2::double_scalarmult_vartime_table(a_scalar, a_point, b_scalar, &BI)
+    }
+
+    /// `r = a * A + b * P`, where `b_table = [P, 3P, 5P, ..., 15P]` as built by
+    /// [`GeP3::odd_multiples`].
+    ///
+    /// [`double_scalarmult_vartime`](GeP2::double_scalarmult_vartime) for a
+    /// second generator other than `B`. Build the table once per generator and
+    /// reuse it.
+    ///
+    /// This is **not** constant time, only use it with public scalars.
+    ///
+    /// # Preconditions
+    ///
+    /// `a[31] <= 127` and `b[31] <= 127`.
+    pub fn double_scalarmult_vartime_with(
+        a_scalar: &[u8],
+        a_point: GeP3,
+        b_scalar: &[u8],
+        b_table: &[GeCached; 8],
+    ) -> GeP2 {
+        GeP2::double_scalarmult_vartime_table(
+            a_scalar, a_point, b_scalar, b_table,
+        )
+    }
+
+    // Both of the above: BI holds GePrecomp, caller tables GeCached.
+    fn double_scalarmult_vartime_table<T>(
+        a_scalar: &[u8],
+        a_point: GeP3,
+        b_scalar: &[u8],
+        bi: &[T; 8],
+    ) -> GeP2
+    where
+        T: Copy,
+        GeP3: Add<T, Output = GeP1P1> + Sub<T, Output = GeP1P1>,
+        // Restated so the window table of A still resolves.
+        GeP3: Add<GeCached, Output = GeP1P1> + Sub<GeCached, Output = GeP1P1>,
+    {
+        let aslide = GeP2::slide(a_scalar);
+        let bslide = GeP2::slide(b_scalar);
+
+        let ai = a_point.odd_multiples(); // A,3A,5A,7A,9A,11A,13A,15A
+
+        let mut r = GeP2::zero();
+
+        let mut i: usize = 255;
+        loop {
+            if aslide[i] != 0 || bslide[i] != 0 {
+                break;
+            }
+            if i == 0 {
+                return r;
+            }
+            i -= 1;
+        }
+
+        loop {
+            let mut t = r.dbl();
+            if aslide[i] > 0 {
+                t = t.to_p3() + ai[(aslide[i] / 2) as usize];
+            } else if aslide[i] < 0 {
+                t = t.to_p3() - ai[(-aslide[i] / 2) as usize];
+            }
+
+            if bslide[i] > 0 {
+                t = t.to_p3() + bi[(bslide[i] / 2) as usize];
+            } else if bslide[i] < 0 {
+                t = t.to_p3() - bi[(-bslide[i] / 2) as usize];
+            }
+
+            r = t.to_p2();
+
+            if i == 0 {
+                return r;
+            }
+            i -= 1;
+        }
+    }
+}
+
+impl GeP3 {
+    /// Decodes a point from its RFC 8032 encoding, the inverse of
+    /// [`to_bytes`](GeP3::to_bytes). Returns `None` unless `s` is 32 bytes
+    /// encoding a curve point.
+    ///
+    /// This is **not** constant time, only use it with public points.
+    pub fn from_bytes_vartime(s: &[u8]) -> Option<GeP3> {
+        let GeP3 { x, y, z, t } = GeP3::from_bytes_negate_vartime(s)?;
+        Some(GeP3 {
+            x: x.neg(),
+            y,
+            z,
+            t: t.neg(),
+        })
+    }
+
+    /// Like [`from_bytes_vartime`](GeP3::from_bytes_vartime), but only accepts
+    /// canonical encodings, the strict decoding of RFC 8032, section 5.1.3:
+    /// `y` must be below `p`, and `x = 0` must come with a clear sign bit (no
+    /// "-0"). Every point has exactly one encoding this accepts.
+    ///
+    /// This is **not** constant time, only use it with public points.
+    pub fn from_bytes_strict(s: &[u8; 32]) -> Option<GeP3> {
+        let point = GeP3::from_bytes_vartime(s)?;
+        if !fixed_time_eq(&point.to_bytes(), s) {
+            return None;
+        }
+        Some(point)
+    }
+
+    /// Decodes the negation of the point `s` encodes, ref10's
+    /// `ge_frombytes_negate_vartime`; verification wants `-A` anyway.
+    ///
+    /// This is **not** constant time, only use it with public points.
+    pub fn from_bytes_negate_vartime(s: &[u8]) -> Option<GeP3> {
+        let y = FieldElement::try_from_bytes(s)?;
+        let z = FE_ONE;
+        let y_squared = y.square();
+        let u = y_squared - FE_ONE;
+        let v = (y_squared * FE_D) + FE_ONE;
+        let v_raise_3 = v.square() * v;
+        let v_raise_7 = v_raise_3.square() * v;
+        let uv7 = v_raise_7 * u; // Is this commutative? u comes second in the code, but not in the
+                                 // notation...
+
+        let mut x = uv7.pow25523() * v_raise_3 * u;
+
+        let vxx = x.square() * v;
+        let check = vxx - u;
+        if check.is_nonzero() {
+            let check2 = vxx + u;
+            if check2.is_nonzero() {
+                return None;
+            }
+            x = x * FE_SQRTM1;
+        }
+
+        let flip = x.is_negative() == ((s[31] >> 7) != 0);
+        x.conditional_negate(i32::from(flip));
+
+        let t = x * y;
+
+        Some(GeP3 { x, y, z, t })
+    }
+
+    /// Constant-time [`from_bytes_negate_vartime`]: picks between the two
+    /// square root candidates with `maybe_set` instead of branching, so the
+    /// timing only depends on whether `s` is a valid encoding at all.
+    ///
+    /// Use it when the point is secret, e.g. a blinded element in an OPRF;
+    /// public keys and signatures can take the vartime version. Both cost
+    /// one `pow25523`, the difference is an extra multiplication by
+    /// `sqrt(-1)` here.
+    ///
+    /// [`from_bytes_negate_vartime`]: GeP3::from_bytes_negate_vartime
+    pub fn from_bytes_negate_ct(s: &[u8]) -> Option<GeP3> {
+        let y = FieldElement::try_from_bytes(s)?;
+        let z = FE_ONE;
+        let y_squared = y.square();
+        let u = y_squared - FE_ONE;
+        let v = (y_squared * FE_D) + FE_ONE;
+        let v_raise_3 = v.square() * v;
+        let v_raise_7 = v_raise_3.square() * v;
+        let uv7 = v_raise_7 * u;
+
+        let mut x = uv7.pow25523() * v_raise_3 * u;
+
+        let vxx = x.square() * v;
+        let is_root = vxx == u;
+        let is_i_root = vxx == u.neg();
+        let x_i = x * FE_SQRTM1;
+        x.maybe_set(&x_i, i32::from(!is_root));
+
+        let flip = x.is_negative() == ((s[31] >> 7) != 0);
+        x.conditional_negate(i32::from(flip));
+
+        let t = x * y;
+
+        if !(is_root | is_i_root) {
+            return None;
+        }
+        Some(GeP3 { x, y, z, t })
+    }
+
+    fn to_p2(&self) -> GeP2 {
+        GeP2 {
+            x: self.x,
+            y: self.y,
+            z: self.z,
+        }
+    }
+
+    /// `(Y + X, Y - X, Z, 2 d T)`, the form the addition formulas take their
+    /// second operand in. Build window tables of any point from these.
+    pub fn to_cached(&self) -> GeCached {
+        GeCached {
+            y_plus_x: self.y + self.x,
+            y_minus_x: self.y - self.x,
+            z: self.z,
+            t2d: self.t * FE_D2,
+        }
+    }
+
+    /// `self + other`, back in P3 form: `(*self + *other).to_p3()`, for
+    /// accumulation loops over a table of cached points.
+    pub fn add_cached(&self, other: &GeCached) -> GeP3 {
+        (*self + *other).to_p3()
+    }
+
+    /// `self + other` for a point of a precomputed table, like
+    /// [`add_cached`](GeP3::add_cached).
+    pub fn add_precomp(&self, other: &GePrecomp) -> GeP3 {
+        (*self + *other).to_p3()
+    }
+
+    /// `[P, 3P, 5P, ..., 15P]`, the table sliding-window digits index into,
+    /// e.g. the `b_table` of [`GeP2::double_scalarmult_vartime_with`].
+    pub fn odd_multiples(&self) -> [GeCached; 8] {
+        let mut ai = [GeCached {
+            y_plus_x: FE_ZERO,
+            y_minus_x: FE_ZERO,
+            z: FE_ZERO,
+            t2d: FE_ZERO,
+        }; 8];
+        ai[0] = self.to_cached();
+        let a2 = self.dbl().to_p3();
+        for i in 1..8 {
+            ai[i] = (a2 + ai[i - 1]).to_p3().to_cached();
+        }
+        ai
+    }
+
+    pub(crate) fn zero() -> GeP3 {
+        GeP3 {
+            x: FE_ZERO,
+            y: FE_ONE,
+            z: FE_ONE,
+            t: FE_ZERO,
+        }
+    }
+
+    pub(crate) fn dbl(&self) -> GeP1P1 { self.to_p2().dbl() }
+
+    /// `2^n * self`. Stays in [`GeP2`] between doublings, which never need `T`,
+    /// and converts to `GeP3` only once at the end.
+    pub fn double_repeatedly(&self, n: u32) -> GeP3 {
+        if n == 0 {
+            return *self;
+        }
+        let mut s = self.to_p2();
+        for _ in 1..n {
+            s = s.dbl().to_p2();
+        }
+        s.dbl().to_p3()
+    }
+
+    /// The affine coordinates `(x, y) = (X/Z, Y/Z)`, with a single
+    /// inversion.
+    pub fn to_affine(&self) -> (FieldElement, FieldElement) {
+        let recip = self.z.invert();
+        (self.x * recip, self.y * recip)
+    }
+
+    /// The RFC 8032 encoding: `y` with the sign of `x` in the top bit.
+    pub fn to_bytes(&self) -> [u8; 32] { self.to_bytes_normalized().0 }
+
+    /// Like `to_bytes`, but writes the encoding into `out`, e.g. straight
+    /// into a larger frame.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `out` is exactly 32 bytes long.
+    pub fn encode_into(&self, out: &mut [u8]) {
+        assert_eq!(out.len(), 32, "encode_into: out must be 32 bytes");
+        out.copy_from_slice(&self.to_bytes());
+    }
+
+    /// The encoding together with the same point normalized to `Z = 1`,
+    /// both from the one inversion [`to_bytes`](GeP3::to_bytes) pays
+    /// anyway. For callers that hash the encoding and keep using the point,
+    /// e.g. `R` in a signature, instead of decoding it again.
+    pub fn to_bytes_normalized(&self) -> ([u8; 32], GeP3) {
+        let (x, y) = self.to_affine();
+        let mut bs = y.to_bytes();
+        bs[31] ^= (if x.is_negative() { 1 } else { 0 }) << 7;
+        let normalized = GeP3 {
+            x,
+            y,
+            z: FE_ONE,
+            t: x * y,
+        };
+        (bs, normalized)
+    }
+
+    /// `clamp(bytes) * B`, encoded: the Edwards form of an X25519 public key.
+    ///
+    /// This is the X25519 clamp applied to the raw bytes, for X25519 keys and
+    /// Montgomery-to-Edwards conversions. Ed25519 keys instead clamp the first
+    /// half of a SHA-512 hash of the seed, which this doesn't do.
+    ///
+    /// Runs in constant time.
+    pub fn mul_base_clamped(bytes: [u8; 32]) -> [u8; 32] {
+        ge_scalarmult_base(&clamp_scalar(bytes)).to_bytes()
+    }
+
+    /// The negation, `-P = (-X : Y : Z : -T)`.
+    pub fn neg(&self) -> GeP3 {
+        GeP3 {
+            x: self.x.neg(),
+            y: self.y,
+            z: self.z,
+            t: self.t.neg(),
+        }
+    }
+
+    /// `r = s * B - c * A`, where `s` and `c` are little-endian, `A` is
+    /// `a_point` and `B` is the Ed25519 base point. This is the
+    /// Schnorr/EdDSA verification equation; note that the base point scalar
+    /// comes first, unlike in [`GeP2::double_scalarmult_vartime`].
+    ///
+    /// This is **not** constant time, only use it with public scalars.
+    ///
+    /// # Preconditions
+    ///
+    /// `s[31] <= 127` and `c[31] <= 127`.
+    pub fn vartime_double_scalarmult_base(
+        s: &[u8],
+        a_point: &GeP3,
+        c: &[u8],
+    ) -> GeP2 {
+        GeP2::double_scalarmult_vartime(c, a_point.neg(), s)
+    }
+
+    /// Whether this is the neutral element `(0, 1)`, without an inversion.
+    pub fn is_identity(&self) -> bool { *self == GeP3::zero() }
+
+    /// Whether the point is one of the eight points of order dividing 8, the
+    /// identity included: `8 P = 0`. A public key or a Diffie-Hellman share
+    /// like that pins the result to at most eight values whatever the
+    /// secret, so protocols must reject it.
+    ///
+    /// This is not a prime-order subgroup check: it returns `false` for a
+    /// point of large order with a small-order component too.
+    ///
+    /// Runs in constant time.
+    pub fn is_small_order(&self) -> bool {
+        self.double_repeatedly(3).is_identity()
+    }
+
+    /// `u = (1 + y) / (1 - y)`, the birational map to the Montgomery curve,
+    /// computed projectively as `(Z + Y) / (Z - Y)`.
+    ///
+    /// The identity (`y = 1`) has no image. It deliberately maps to `u = 0`,
+    /// the all-zero encoding X25519 uses for the point at infinity: `invert`
+    /// sends the zero denominator to zero rather than failing, so there's
+    /// no panic and no garbage. The order-2 point `(0, -1)` maps to `u = 0`
+    /// too, its genuine image. Either way a ladder on the result outputs
+    /// all zeros, the shared secret libsodium's `crypto_scalarmult` rejects
+    /// and callers doing key agreement should reject as well.
+    pub fn to_montgomery(&self) -> FieldElement {
+        (self.z + self.y) * (self.z - self.y).invert()
+    }
+}
+
+// Projective coordinates aren't unique, so compare X1/Z1 with X2/Z2 and
+// Y1/Z1 with Y2/Z2 by cross-multiplying, which avoids the inversions of
+// to_bytes. Both comparisons always run, so this is constant time.
+impl PartialEq for GeP3 {
+    fn eq(&self, other: &GeP3) -> bool {
+        let [x1z2, x2z1, y1z2, y2z1] = FieldElement::mul4(
+            [self.x, other.x, self.y, other.y],
+            [other.z, self.z, other.z, self.z],
+        );
+        (x1z2 == x2z1) & (y1z2 == y2z1)
+    }
+}
+
+impl Eq for GeP3 {}
+
+// Points print as their 32-byte encoding, which doesn't depend on the
+// representation. Z = 0 can't panic either, it inverts to zero.
+fn fmt_encoding(
+    name: &str,
+    bytes: &[u8; 32],
+    f: &mut fmt::Formatter,
+) -> fmt::Result {
+    write!(f, "{}(", name)?;
+    bytes.iter().try_for_each(|b| write!(f, "{:02x}", b))?;
+    f.write_str(")")
+}
+
+impl fmt::Debug for GeP2 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_encoding("GeP2", &self.to_bytes(), f)
+    }
+}
+
+impl fmt::Debug for GeP3 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_encoding("GeP3", &self.to_bytes(), f)
+    }
+}
+
+impl Add<GeCached> for GeP3 {
+    type Output = GeP1P1;
+
+    fn add(self, _rhs: GeCached) -> GeP1P1 {
+        let y1_plus_x1 = self.y + self.x;
+        let y1_minus_x1 = self.y - self.x;
+        let [a, b, c, zz] = FieldElement::mul4(
+            [y1_plus_x1, y1_minus_x1, _rhs.t2d, self.z],
+            [_rhs.y_plus_x, _rhs.y_minus_x, self.t, _rhs.z],
+        );
+        let d = zz + zz;
+        let x3 = a - b;
+        let y3 = a + b;
+        let z3 = d + c;
+        let t3 = d - c;
+
+        GeP1P1 {
+            x: x3,
+            y: y3,
+            z: z3,
+            t: t3,
+        }
+    }
+}
+
+impl Add<GePrecomp> for GeP3 {
+    type Output = GeP1P1;
+
+    fn add(self, _rhs: GePrecomp) -> GeP1P1 {
+        let y1_plus_x1 = self.y + self.x;
+        let y1_minus_x1 = self.y - self.x;
+        let a = y1_plus_x1 * _rhs.y_plus_x;
+        let b = y1_minus_x1 * _rhs.y_minus_x;
+        let c = _rhs.xy2d * self.t;
+        let d = self.z + self.z;
+        let x3 = a - b;
+        let y3 = a + b;
+        let z3 = d + c;
+        let t3 = d - c;
+
+        GeP1P1 {
+            x: x3,
+            y: y3,
+            z: z3,
+            t: t3,
+        }
+    }
+}
+
+impl Sub<GeCached> for GeP3 {
+    type Output = GeP1P1;
+
+    fn sub(self, _rhs: GeCached) -> GeP1P1 {
+        let y1_plus_x1 = self.y + self.x;
+        let y1_minus_x1 = self.y - self.x;
+        let [a, b, c, zz] = FieldElement::mul4(
+            [y1_plus_x1, y1_minus_x1, _rhs.t2d, self.z],
+            [_rhs.y_minus_x, _rhs.y_plus_x, self.t, _rhs.z],
+        );
+        let d = zz + zz;
+        let x3 = a - b;
+        let y3 = a + b;
+        let z3 = d - c;
+        let t3 = d + c;
+
+        GeP1P1 {
+            x: x3,
+            y: y3,
+            z: z3,
+            t: t3,
+        }
+    }
+}
+
+impl Sub<GePrecomp> for GeP3 {
+    type Output = GeP1P1;
+
+    fn sub(self, _rhs: GePrecomp) -> GeP1P1 {
+        let y1_plus_x1 = self.y + self.x;
+        let y1_minus_x1 = self.y - self.x;
+        let a = y1_plus_x1 * _rhs.y_minus_x;
+        let b = y1_minus_x1 * _rhs.y_plus_x;
+        let c = _rhs.xy2d * self.t;
+        let d = self.z + self.z;
+        let x3 = a - b;
+        let y3 = a + b;
+        let z3 = d - c;
+        let t3 = d + c;
+
+        GeP1P1 {
+            x: x3,
+            y: y3,
+            z: z3,
+            t: t3,
+        }
+    }
+}
+
+// Full point addition and subtraction, going through the cached form of
+// rhs. Chains of additions of the same point should cache it once instead.
+impl Add for GeP3 {
+    type Output = GeP3;
+
+    fn add(self, rhs: GeP3) -> GeP3 { (self + rhs.to_cached()).to_p3() }
+}
+
+impl Sub for GeP3 {
+    type Output = GeP3;
+
+    fn sub(self, rhs: GeP3) -> GeP3 { (self - rhs.to_cached()).to_p3() }
+}
+
+// Drops T, which costs nothing.
+impl From<GeP3> for GeP2 {
+    fn from(p: GeP3) -> GeP2 { p.to_p2() }
+}
+
+// Three field multiplications.
+impl From<GeP1P1> for GeP2 {
+    fn from(p: GeP1P1) -> GeP2 { p.to_p2() }
+}
+
+// Four field multiplications.
+impl From<GeP1P1> for GeP3 {
+    fn from(p: GeP1P1) -> GeP3 { p.to_p3() }
+}
+
+#[inline]
+fn equal(b: u8, c: u8) -> i32 {
+    let x = b ^ c; // 0: yes; 1..255: no
+    let mut y = u32::from(x); // 0: yes; 1..255: no
+    y = y.wrapping_sub(1); // 4294967295: yes; 0..254: no
+    y >>= 31; // 1: yes; 0: no
+    y as i32
+}
+
+#[inline]
+fn negative(b: i8) -> u8 {
+    let mut x = i64::from(b) as u64;
+    x >>= 63; // 1: yes; 0: no
+    x as u8
+}
+
+impl GePrecomp {
+    fn zero() -> GePrecomp {
+        GePrecomp {
+            y_plus_x: FE_ONE,
+            y_minus_x: FE_ONE,
+            xy2d: FE_ZERO,
+        }
+    }
+
+    /// `(y + x, y - x, 2 d x y)` in affine coordinates, the form of the base
+    /// point tables, for building such a table for another fixed point.
+    ///
+    /// Costs an inversion; additions with it are cheaper than with
+    /// [`GeCached`].
+    pub fn from_p3(p: &GeP3) -> GePrecomp {
+        let recip = p.z.invert();
+        let x = p.x * recip;
+        let y = p.y * recip;
+        GePrecomp {
+            y_plus_x: y + x,
+            y_minus_x: y - x,
+            xy2d: x * y * FE_D2,
+        }
+    }
+
+    /// Sets `self` to `other` if `do_swap` is 1, in constant time.
+    pub fn maybe_set(&mut self, other: &GePrecomp, do_swap: i32) {
+        self.y_plus_x.maybe_set(&other.y_plus_x, do_swap);
+        self.y_minus_x.maybe_set(&other.y_minus_x, do_swap);
+        self.xy2d.maybe_set(&other.xy2d, do_swap);
+    }
+
+    /// `b * 256^pos * B`, looked up in the base point table with
+    /// [`ge_precomp_select`].
+    pub fn select(pos: usize, b: i8) -> GePrecomp {
+        ge_precomp_select(&GE_PRECOMP_BASE[pos], b)
+    }
+}
+
+impl GeCached {
+    fn zero() -> GeCached {
+        GeCached {
+            y_plus_x: FE_ONE,
+            y_minus_x: FE_ONE,
+            z: FE_ONE,
+            t2d: FE_ZERO,
+        }
+    }
+
+    /// Sets `self` to `other` if `do_swap` is 1, in constant time.
+    pub fn maybe_set(&mut self, other: &GeCached, do_swap: i32) {
+        self.y_plus_x.maybe_set(&other.y_plus_x, do_swap);
+        self.y_minus_x.maybe_set(&other.y_minus_x, do_swap);
+        self.z.maybe_set(&other.z, do_swap);
+        self.t2d.maybe_set(&other.t2d, do_swap);
+    }
+}
+
+// babs = |b|, without branching on b.
+#[inline]
+fn abs_index(b: i8) -> (u8, u8) {
+    let bnegative: u8 = negative(b);
+    let babs: u8 = (b - (((-(bnegative as i8)) & b) << 1)) as u8;
+    (bnegative, babs)
+}
+
+/// Constant-time table lookup: returns `b * P` for a table holding
+/// `[P, 2P, ..., 8P]`, the identity for `b = 0`.
+///
+/// Every entry is read whatever `b` is, so timing doesn't depend on it.
+///
+/// # Preconditions
+///
+/// `-8 <= b <= 8`.
+pub fn ge_precomp_select(table: &[GePrecomp; 8], b: i8) -> GePrecomp {
+    let (bnegative, babs) = abs_index(b);
+    let mut t = GePrecomp::zero();
+    for (i, entry) in table.iter().enumerate() {
+        t.maybe_set(entry, equal(babs, i as u8 + 1));
+    }
+    let minus_t = GePrecomp {
+        y_plus_x: t.y_minus_x,
+        y_minus_x: t.y_plus_x,
+        xy2d: t.xy2d.neg(),
+    };
+    t.maybe_set(&minus_t, i32::from(bnegative));
+    t
+}
+
+/// Same as [`ge_precomp_select`], for tables of [`GeCached`].
+///
+/// # Preconditions
+///
+/// `-8 <= b <= 8`.
+pub fn ge_cached_select(table: &[GeCached; 8], b: i8) -> GeCached {
+    let (bnegative, babs) = abs_index(b);
+    let mut t = GeCached::zero();
+    for (i, entry) in table.iter().enumerate() {
+        t.maybe_set(entry, equal(babs, i as u8 + 1));
+    }
+    let minus_t = GeCached {
+        y_plus_x: t.y_minus_x,
+        y_minus_x: t.y_plus_x,
+        z: t.z,
+        t2d: t.t2d.neg(),
+    };
+    t.maybe_set(&minus_t, i32::from(bnegative));
+    t
+}
+
+// Writes a = a[0]+256*a[1]+...+256^31 a[31] as
+// es[0]+16*es[1]+...+16^63 es[63] with each es[i] between -8 and 8.
+//
+// es is filled in place rather than returned, so the digits of a secret
+// scalar exist in one buffer only, which the caller wipes.
+//
+// Preconditions:
+//   a[31] <= 127
+fn radix16(a: &[u8; 32], es: &mut [i8; 64]) {
+    for i in 0..32 {
+        es[2 * i] = (a[i] & 15) as i8;
+        es[2 * i + 1] = ((a[i] >> 4) & 15) as i8;
+    }
+    // each es[i] is between 0 and 15
+    // es[63] is between 0 and 7
+
+    let mut carry: i8 = 0;
+    for i in es.iter_mut().take(63) {
+        *i += carry;
+        carry = *i + 8;
+        carry >>= 4;
+        *i -= carry << 4;
+    }
+    es[63] += carry;
+    // each es[i] is between -8 and 8
+
+    zeroize(slice::from_mut(&mut carry));
+}
+
+/// `h = a * B`, where `a` is little-endian and `B` is the Ed25519 base point
+/// `(x, 4/5)` with `x` positive.
+///
+/// Constant time in `a`. The signed digits of `a` are zeroized before
+/// returning, so they don't linger on the stack.
+///
+/// # Preconditions
+///
+/// `a[31] <= 127`.
+pub fn ge_scalarmult_base(a: &[u8; 32]) -> GeP3 {
+    let mut es = [0i8; 64];
+    radix16(a, &mut es);
+    let mut r: GeP1P1;
+    let mut t: GePrecomp;
+
+    let mut h = GeP3::zero();
+    for i in (1..64).step_by(2) {
+        t = GePrecomp::select(i / 2, es[i]);
+        r = h + t;
+        h = r.to_p3();
+    }
+
+    h = h.double_repeatedly(4);
+
+    for i in (0..64).step_by(2) {
+        t = GePrecomp::select(i / 2, es[i]);
+        r = h + t;
+        h = r.to_p3();
+    }
+
+    zeroize(&mut es);
+    h
+}
+
+/// `h = a * A`, in constant time in both `a` and `A`.
+///
+/// Same signed radix-16 windows as [`ge_scalarmult_base`], looked up in a
+/// table of `A, 2A, ..., 8A` with `ge_cached_select`. Every window does
+/// four doublings and one addition, whatever the digit, so neither the
+/// scalar nor the point leak through timing or memory access; use it when
+/// the point is secret too, e.g. in OPRF evaluation. The digits of `a` and
+/// the table of multiples are zeroized before returning.
+///
+/// # Preconditions
+///
+/// `a[31] <= 127`.
+pub fn ge_scalarmult_ct(a: &[u8; 32], point: &GeP3) -> GeP3 {
+    debug_assert!(a[31] <= 127);
+    let mut es = [0i8; 64];
+    radix16(a, &mut es);
+
+    let mut table = [point.to_cached(); 8];
+    for i in 1..8 {
+        table[i] = (*point + table[i - 1]).to_p3().to_cached();
+    }
+
+    let mut h = GeP3::zero();
+    for &e in es.iter().rev() {
+        h = h.double_repeatedly(4);
+        h = (h + ge_cached_select(&table, e)).to_p3();
+    }
+
+    zeroize(&mut es);
+    zeroize(&mut table);
+    h
+}
+
+/// `h = a * B + b * H`, where `B` is the Ed25519 base point and `H` a
+/// second generator.
+///
+/// A Pedersen commitment to `a` with blinding factor `b`, as long as nobody
+/// knows the discrete log of `H` to the base `B`; `hash_to_edwards` gives
+/// such an `H`. Commitments add up, with the scalars added mod `l`:
+/// `commit(a1, b1, H) + commit(a2, b2, H) = commit(a1 + a2, b1 + b2, H)`.
+///
+/// Constant time in `a`, `b` and `H`.
+///
+/// # Preconditions
+///
+/// `a[31] <= 127` and `b[31] <= 127`.
+pub fn commit(a: &[u8; 32], b: &[u8; 32], h: &GeP3) -> GeP3 {
+    ge_scalarmult_base(a) + ge_scalarmult_ct(b, h)
+}
+
+/// Reduces the 64-byte little-endian integer in `s` modulo
+/// `l = 2^252 + 27742317777372353535851937790883648493`, writing the
+/// result to `s[..32]`.
+///
+/// Takes a slice like ref10; [`scalar_reduce`](crate::scalar_reduce) is the
+/// array-typed version, checked at compile time.
+///
+/// # Panics
+///
+/// Panics unless `s` is 64 bytes long.
+pub fn sc_reduce(s: &mut [u8]) {
+    assert_eq!(s.len(), 64, "sc_reduce: s must be 64 bytes");
+
+    let mut s0: i64 = 2_097_151 & load_3i(s);
+    let mut s1: i64 = 2_097_151 & (load_4i(&s[2..6]) >> 5);
+    let mut s2: i64 = 2_097_151 & (load_3i(&s[5..8]) >> 2);
+    let mut s3: i64 = 2_097_151 & (load_4i(&s[7..11]) >> 7);
+    let mut s4: i64 = 2_097_151 & (load_4i(&s[10..14]) >> 4);
+    let mut s5: i64 = 2_097_151 & (load_3i(&s[13..16]) >> 1);
+    let mut s6: i64 = 2_097_151 & (load_4i(&s[15..19]) >> 6);
+    let mut s7: i64 = 2_097_151 & (load_3i(&s[18..21]) >> 3);
+    let mut s8: i64 = 2_097_151 & load_3i(&s[21..24]);
+    let mut s9: i64 = 2_097_151 & (load_4i(&s[23..27]) >> 5);
+    let mut s10: i64 = 2_097_151 & (load_3i(&s[26..29]) >> 2);
+    let mut s11: i64 = 2_097_151 & (load_4i(&s[28..32]) >> 7);
+    let mut s12: i64 = 2_097_151 & (load_4i(&s[31..35]) >> 4);
+    let mut s13: i64 = 2_097_151 & (load_3i(&s[34..37]) >> 1);
+    let mut s14: i64 = 2_097_151 & (load_4i(&s[36..40]) >> 6);
+    let mut s15: i64 = 2_097_151 & (load_3i(&s[39..42]) >> 3);
+    let mut s16: i64 = 2_097_151 & load_3i(&s[42..45]);
+    let mut s17: i64 = 2_097_151 & (load_4i(&s[44..48]) >> 5);
+    let s18: i64 = 2_097_151 & (load_3i(&s[47..50]) >> 2);
+    let s19: i64 = 2_097_151 & (load_4i(&s[49..53]) >> 7);
+    let s20: i64 = 2_097_151 & (load_4i(&s[52..56]) >> 4);
+    let s21: i64 = 2_097_151 & (load_3i(&s[55..58]) >> 1);
+    let s22: i64 = 2_097_151 & (load_4i(&s[57..61]) >> 6);
+    let s23: i64 = load_4i(&s[60..64]) >> 3;
+    let mut carry0: i64;
+    let mut carry1: i64;
+    let mut carry2: i64;
+    let mut carry3: i64;
+    let mut carry4: i64;
+    let mut carry5: i64;
+    let mut carry6: i64;
+    let mut carry7: i64;
+    let mut carry8: i64;
+    let mut carry9: i64;
+    let mut carry10: i64;
+    let mut carry11: i64;
+    let carry12: i64;
+    let carry13: i64;
+    let carry14: i64;
+    let carry15: i64;
+    let carry16: i64;
+
+    s11 += s23 * 666_643;
+    s12 += s23 * 470_296;
+    s13 += s23 * 654_183;
+    s14 -= s23 * 997_805;
+    s15 += s23 * 136_657;
+    s16 -= s23 * 683_901;
+
+    s10 += s22 * 666_643;
+    s11 += s22 * 470_296;
+    s12 += s22 * 654_183;
+    s13 -= s22 * 997_805;
+    s14 += s22 * 136_657;
+    s15 -= s22 * 683_901;
+
+    s9 += s21 * 666_643;
+    s10 += s21 * 470_296;
+    s11 += s21 * 654_183;
+    s12 -= s21 * 997_805;
+    s13 += s21 * 136_657;
+    s14 -= s21 * 683_901;
+
+    s8 += s20 * 666_643;
+    s9 += s20 * 470_296;
+    s10 += s20 * 654_183;
+    s11 -= s20 * 997_805;
+    s12 += s20 * 136_657;
+    s13 -= s20 * 683_901;
+
+    s7 += s19 * 666_643;
+    s8 += s19 * 470_296;
+    s9 += s19 * 654_183;
+    s10 -= s19 * 997_805;
+    s11 += s19 * 136_657;
+    s12 -= s19 * 683_901;
+
+    s6 += s18 * 666_643;
+    s7 += s18 * 470_296;
+    s8 += s18 * 654_183;
+    s9 -= s18 * 997_805;
+    s10 += s18 * 136_657;
+    s11 -= s18 * 683_901;
+
+    carry6 = (s6 + (1 << 20)) >> 21;
+    s7 += carry6;
+    s6 -= carry6 << 21;
+    carry8 = (s8 + (1 << 20)) >> 21;
+    s9 += carry8;
+    s8 -= carry8 << 21;
+    carry10 = (s10 + (1 << 20)) >> 21;
+    s11 += carry10;
+    s10 -= carry10 << 21;
+    carry12 = (s12 + (1 << 20)) >> 21;
+    s13 += carry12;
+    s12 -= carry12 << 21;
+    carry14 = (s14 + (1 << 20)) >> 21;
+    s15 += carry14;
+    s14 -= carry14 << 21;
+    carry16 = (s16 + (1 << 20)) >> 21;
+    s17 += carry16;
+    s16 -= carry16 << 21;
+
+    carry7 = (s7 + (1 << 20)) >> 21;
+    s8 += carry7;
+    s7 -= carry7 << 21;
+    carry9 = (s9 + (1 << 20)) >> 21;
+    s10 += carry9;
+    s9 -= carry9 << 21;
+    carry11 = (s11 + (1 << 20)) >> 21;
+    s12 += carry11;
+    s11 -= carry11 << 21;
+    carry13 = (s13 + (1 << 20)) >> 21;
+    s14 += carry13;
+    s13 -= carry13 << 21;
+    carry15 = (s15 + (1 << 20)) >> 21;
+    s16 += carry15;
+    s15 -= carry15 << 21;
+
+    s5 += s17 * 666_643;
+    s6 += s17 * 470_296;
+    s7 += s17 * 654_183;
+    s8 -= s17 * 997_805;
+    s9 += s17 * 136_657;
+    s10 -= s17 * 683_901;
+
+    s4 += s16 * 666_643;
+    s5 += s16 * 470_296;
+    s6 += s16 * 654_183;
+    s7 -= s16 * 997_805;
+    s8 += s16 * 136_657;
+    s9 -= s16 * 683_901;
+
+    s3 += s15 * 666_643;
+    s4 += s15 * 470_296;
+    s5 += s15 * 654_183;
+    s6 -= s15 * 997_805;
+    s7 += s15 * 136_657;
+    s8 -= s15 * 683_901;
+
+    s2 += s14 * 666_643;
+    s3 += s14 * 470_296;
+    s4 += s14 * 654_183;
+    s5 -= s14 * 997_805;
+    s6 += s14 * 136_657;
+    s7 -= s14 * 683_901;
+
+    s1 += s13 * 666_643;
+    s2 += s13 * 470_296;
+    s3 += s13 * 654_183;
+    s4 -= s13 * 997_805;
+    s5 += s13 * 136_657;
+    s6 -= s13 * 683_901;
+
+    s0 += s12 * 666_643;
+    s1 += s12 * 470_296;
+    s2 += s12 * 654_183;
+    s3 -= s12 * 997_805;
+    s4 += s12 * 136_657;
+    s5 -= s12 * 683_901;
+    s12 = 0;
+
+    carry0 = (s0 + (1 << 20)) >> 21;
+    s1 += carry0;
+    s0 -= carry0 << 21;
+    carry2 = (s2 + (1 << 20)) >> 21;
+    s3 += carry2;
+    s2 -= carry2 << 21;
+    carry4 = (s4 + (1 << 20)) >> 21;
+    s5 += carry4;
+    s4 -= carry4 << 21;
+    carry6 = (s6 + (1 << 20)) >> 21;
+    s7 += carry6;
+    s6 -= carry6 << 21;
+    carry8 = (s8 + (1 << 20)) >> 21;
+    s9 += carry8;
+    s8 -= carry8 << 21;
+    carry10 = (s10 + (1 << 20)) >> 21;
+    s11 += carry10;
+    s10 -= carry10 << 21;
+
+    carry1 = (s1 + (1 << 20)) >> 21;
+    s2 += carry1;
+    s1 -= carry1 << 21;
+    carry3 = (s3 + (1 << 20)) >> 21;
+    s4 += carry3;
+    s3 -= carry3 << 21;
+    carry5 = (s5 + (1 << 20)) >> 21;
+    s6 += carry5;
+    s5 -= carry5 << 21;
+    carry7 = (s7 + (1 << 20)) >> 21;
+    s8 += carry7;
+    s7 -= carry7 << 21;
+    carry9 = (s9 + (1 << 20)) >> 21;
+    s10 += carry9;
+    s9 -= carry9 << 21;
+    carry11 = (s11 + (1 << 20)) >> 21;
+    s12 += carry11;
+    s11 -= carry11 << 21;
+
+    s0 += s12 * 666_643;
+    s1 += s12 * 470_296;
+    s2 += s12 * 654_183;
+    s3 -= s12 * 997_805;
+    s4 += s12 * 136_657;
+    s5 -= s12 * 683_901;
+    s12 = 0;
+
+    carry0 = s0 >> 21;
+    s1 += carry0;
+    s0 -= carry0 << 21;
+    carry1 = s1 >> 21;
+    s2 += carry1;
+    s1 -= carry1 << 21;
+    carry2 = s2 >> 21;
+    s3 += carry2;
+    s2 -= carry2 << 21;
+    carry3 = s3 >> 21;
+    s4 += carry3;
+    s3 -= carry3 << 21;
+    carry4 = s4 >> 21;
+    s5 += carry4;
+    s4 -= carry4 << 21;
+    carry5 = s5 >> 21;
+    s6 += carry5;
+    s5 -= carry5 << 21;
+    carry6 = s6 >> 21;
+    s7 += carry6;
+    s6 -= carry6 << 21;
+    carry7 = s7 >> 21;
+    s8 += carry7;
+    s7 -= carry7 << 21;
+    carry8 = s8 >> 21;
+    s9 += carry8;
+    s8 -= carry8 << 21;
+    carry9 = s9 >> 21;
+    s10 += carry9;
+    s9 -= carry9 << 21;
+    carry10 = s10 >> 21;
+    s11 += carry10;
+    s10 -= carry10 << 21;
+    carry11 = s11 >> 21;
+    s12 += carry11;
+    s11 -= carry11 << 21;
+
+    s0 += s12 * 666_643;
+    s1 += s12 * 470_296;
+    s2 += s12 * 654_183;
+    s3 -= s12 * 997_805;
+    s4 += s12 * 136_657;
+    s5 -= s12 * 683_901;
+
+    carry0 = s0 >> 21;
+    s1 += carry0;
+    s0 -= carry0 << 21;
+    carry1 = s1 >> 21;
+    s2 += carry1;
+    s1 -= carry1 << 21;
+    carry2 = s2 >> 21;
+    s3 += carry2;
+    s2 -= carry2 << 21;
+    carry3 = s3 >> 21;
+    s4 += carry3;
+    s3 -= carry3 << 21;
+    carry4 = s4 >> 21;
+    s5 += carry4;
+    s4 -= carry4 << 21;
+    carry5 = s5 >> 21;
+    s6 += carry5;
+    s5 -= carry5 << 21;
+    carry6 = s6 >> 21;
+    s7 += carry6;
+    s6 -= carry6 << 21;
+    carry7 = s7 >> 21;
+    s8 += carry7;
+    s7 -= carry7 << 21;
+    carry8 = s8 >> 21;
+    s9 += carry8;
+    s8 -= carry8 << 21;
+    carry9 = s9 >> 21;
+    s10 += carry9;
+    s9 -= carry9 << 21;
+    carry10 = s10 >> 21;
+    s11 += carry10;
+    s10 -= carry10 << 21;
+
+    s[0] = s0 as u8;
+    s[1] = (s0 >> 8) as u8;
+    s[2] = ((s0 >> 16) | (s1 << 5)) as u8;
+    s[3] = (s1 >> 3) as u8;
+    s[4] = (s1 >> 11) as u8;
+    s[5] = ((s1 >> 19) | (s2 << 2)) as u8;
+    s[6] = (s2 >> 6) as u8;
+    s[7] = ((s2 >> 14) | (s3 << 7)) as u8;
+    s[8] = (s3 >> 1) as u8;
+    s[9] = (s3 >> 9) as u8;
+    s[10] = ((s3 >> 17) | (s4 << 4)) as u8;
+    s[11] = (s4 >> 4) as u8;
+    s[12] = (s4 >> 12) as u8;
+    s[13] = ((s4 >> 20) | (s5 << 1)) as u8;
+    s[14] = (s5 >> 7) as u8;
+    s[15] = ((s5 >> 15) | (s6 << 6)) as u8;
+    s[16] = (s6 >> 2) as u8;
+    s[17] = (s6 >> 10) as u8;
+    s[18] = ((s6 >> 18) | (s7 << 3)) as u8;
+    s[19] = (s7 >> 5) as u8;
+    s[20] = (s7 >> 13) as u8;
+    s[21] = s8 as u8;
+    s[22] = (s8 >> 8) as u8;
+    s[23] = ((s8 >> 16) | (s9 << 5)) as u8;
+    s[24] = (s9 >> 3) as u8;
+    s[25] = (s9 >> 11) as u8;
+    s[26] = ((s9 >> 19) | (s10 << 2)) as u8;
+    s[27] = (s10 >> 6) as u8;
+    s[28] = ((s10 >> 14) | (s11 << 7)) as u8;
+    s[29] = (s11 >> 1) as u8;
+    s[30] = (s11 >> 9) as u8;
+    s[31] = (s11 >> 17) as u8;
+}
+
+/// `s = (a * b + c) mod l`, with every value 32 little-endian bytes and
+/// `l = 2^252 + 27742317777372353535851937790883648493`.
+///
+/// Takes slices like ref10; [`scalar_muladd`](crate::scalar_muladd) is the
+/// array-typed version.
+///
+/// # Panics
+///
+/// Panics unless all four slices are 32 bytes long.
+pub fn sc_muladd(s: &mut [u8], a: &[u8], b: &[u8], c: &[u8]) {
+    assert_eq!(s.len(), 32, "sc_muladd: s must be 32 bytes");
+    assert_eq!(a.len(), 32, "sc_muladd: a must be 32 bytes");
+    assert_eq!(b.len(), 32, "sc_muladd: b must be 32 bytes");
+    assert_eq!(c.len(), 32, "sc_muladd: c must be 32 bytes");
+
+    let a0 = 2_097_151 & load_3i(&a[0..3]);
+    let a1 = 2_097_151 & (load_4i(&a[2..6]) >> 5);
+    let a2 = 2_097_151 & (load_3i(&a[5..8]) >> 2);
+    let a3 = 2_097_151 & (load_4i(&a[7..11]) >> 7);
+    let a4 = 2_097_151 & (load_4i(&a[10..14]) >> 4);
+    let a5 = 2_097_151 & (load_3i(&a[13..16]) >> 1);
+    let a6 = 2_097_151 & (load_4i(&a[15..19]) >> 6);
+    let a7 = 2_097_151 & (load_3i(&a[18..21]) >> 3);
+    let a8 = 2_097_151 & load_3i(&a[21..24]);
+    let a9 = 2_097_151 & (load_4i(&a[23..27]) >> 5);
+    let a10 = 2_097_151 & (load_3i(&a[26..29]) >> 2);
+    let a11 = load_4i(&a[28..32]) >> 7;
+    let b0 = 2_097_151 & load_3i(&b[0..3]);
+    let b1 = 2_097_151 & (load_4i(&b[2..6]) >> 5);
+    let b2 = 2_097_151 & (load_3i(&b[5..8]) >> 2);
+    let b3 = 2_097_151 & (load_4i(&b[7..11]) >> 7);
+    let b4 = 2_097_151 & (load_4i(&b[10..14]) >> 4);
+    let b5 = 2_097_151 & (load_3i(&b[13..16]) >> 1);
+    let b6 = 2_097_151 & (load_4i(&b[15..19]) >> 6);
+    let b7 = 2_097_151 & (load_3i(&b[18..21]) >> 3);
+    let b8 = 2_097_151 & load_3i(&b[21..24]);
+    let b9 = 2_097_151 & (load_4i(&b[23..27]) >> 5);
+    let b10 = 2_097_151 & (load_3i(&b[26..29]) >> 2);
+    let b11 = load_4i(&b[28..32]) >> 7;
+    let c0 = 2_097_151 & load_3i(&c[0..3]);
+    let c1 = 2_097_151 & (load_4i(&c[2..6]) >> 5);
+    let c2 = 2_097_151 & (load_3i(&c[5..8]) >> 2);
+    let c3 = 2_097_151 & (load_4i(&c[7..11]) >> 7);
+    let c4 = 2_097_151 & (load_4i(&c[10..14]) >> 4);
+    let c5 = 2_097_151 & (load_3i(&c[13..16]) >> 1);
+    let c6 = 2_097_151 & (load_4i(&c[15..19]) >> 6);
+    let c7 = 2_097_151 & (load_3i(&c[18..21]) >> 3);
+    let c8 = 2_097_151 & load_3i(&c[21..24]);
+    let c9 = 2_097_151 & (load_4i(&c[23..27]) >> 5);
+    let c10 = 2_097_151 & (load_3i(&c[26..29]) >> 2);
+    let c11 = load_4i(&c[28..32]) >> 7;
+    let mut s0: i64;
+    let mut s1: i64;
+    let mut s2: i64;
+    let mut s3: i64;
+    let mut s4: i64;
+    let mut s5: i64;
+    let mut s6: i64;
+    let mut s7: i64;
+    let mut s8: i64;
+    let mut s9: i64;
+    let mut s10: i64;
+    let mut s11: i64;
+    let mut s12: i64;
+    let mut s13: i64;
+    let mut s14: i64;
+    let mut s15: i64;
+    let mut s16: i64;
+    let mut s17: i64;
+    let mut s18: i64;
+    let mut s19: i64;
+    let mut s20: i64;
+    let mut s21: i64;
+    let mut s22: i64;
+    let mut s23: i64;
+    let mut carry0: i64;
+    let mut carry1: i64;
+    let mut carry2: i64;
+    let mut carry3: i64;
+    let mut carry4: i64;
+    let mut carry5: i64;
+    let mut carry6: i64;
+    let mut carry7: i64;
+    let mut carry8: i64;
+    let mut carry9: i64;
+    let mut carry10: i64;
+    let mut carry11: i64;
+    let mut carry12: i64;
+    let mut carry13: i64;
+    let mut carry14: i64;
+    let mut carry15: i64;
+    let mut carry16: i64;
+    let carry17: i64;
+    let carry18: i64;
+    let carry19: i64;
+    let carry20: i64;
+    let carry21: i64;
+    let carry22: i64;
+
+    s0 = c0 + a0 * b0;
+    s1 = c1 + a0 * b1 + a1 * b0;
+    s2 = c2 + a0 * b2 + a1 * b1 + a2 * b0;
+    s3 = c3 + a0 * b3 + a1 * b2 + a2 * b1 + a3 * b0;
+    s4 = c4 + a0 * b4 + a1 * b3 + a2 * b2 + a3 * b1 + a4 * b0;
+    s5 = c5 + a0 * b5 + a1 * b4 + a2 * b3 + a3 * b2 + a4 * b1 + a5 * b0;
+    s6 = c6
+        + a0 * b6
+        + a1 * b5
+        + a2 * b4
+        + a3 * b3
+        + a4 * b2
+        + a5 * b1
+        + a6 * b0;
+    s7 = c7
+        + a0 * b7
+        + a1 * b6
+        + a2 * b5
+        + a3 * b4
+        + a4 * b3
+        + a5 * b2
+        + a6 * b1
+        + a7 * b0;
+    s8 = c8
+        + a0 * b8
+        + a1 * b7
+        + a2 * b6
+        + a3 * b5
+        + a4 * b4
+        + a5 * b3
+        + a6 * b2
+        + a7 * b1
+        + a8 * b0;
+    s9 = c9
+        + a0 * b9
+        + a1 * b8
+        + a2 * b7
+        + a3 * b6
+        + a4 * b5
+        + a5 * b4
+        + a6 * b3
+        + a7 * b2
+        + a8 * b1
+        + a9 * b0;
+    s10 = c10
+        + a0 * b10
+        + a1 * b9
+        + a2 * b8
+        + a3 * b7
+        + a4 * b6
+        + a5 * b5
+        + a6 * b4
+        + a7 * b3
+        + a8 * b2
+        + a9 * b1
+        + a10 * b0;
+    s11 = c11
+        + a0 * b11
+        + a1 * b10
+        + a2 * b9
+        + a3 * b8
+        + a4 * b7
+        + a5 * b6
+        + a6 * b5
+        + a7 * b4
+        + a8 * b3
+        + a9 * b2
+        + a10 * b1
+        + a11 * b0;
+    s12 = a1 * b11
+        + a2 * b10
+        + a3 * b9
+        + a4 * b8
+        + a5 * b7
+        + a6 * b6
+        + a7 * b5
+        + a8 * b4
+        + a9 * b3
+        + a10 * b2
+        + a11 * b1;
+    s13 = a2 * b11
+        + a3 * b10
+        + a4 * b9
+        + a5 * b8
+        + a6 * b7
+        + a7 * b6
+        + a8 * b5
+        + a9 * b4
+        + a10 * b3
+        + a11 * b2;
+    s14 = a3 * b11
+        + a4 * b10
+        + a5 * b9
+        + a6 * b8
+        + a7 * b7
+        + a8 * b6
+        + a9 * b5
+        + a10 * b4
+        + a11 * b3;
+    s15 = a4 * b11
+        + a5 * b10
+        + a6 * b9
+        + a7 * b8
+        + a8 * b7
+        + a9 * b6
+        + a10 * b5
+        + a11 * b4;
+    s16 =
+        a5 * b11 + a6 * b10 + a7 * b9 + a8 * b8 + a9 * b7 + a10 * b6 + a11 * b5;
+    s17 = a6 * b11 + a7 * b10 + a8 * b9 + a9 * b8 + a10 * b7 + a11 * b6;
+    s18 = a7 * b11 + a8 * b10 + a9 * b9 + a10 * b8 + a11 * b7;
+    s19 = a8 * b11 + a9 * b10 + a10 * b9 + a11 * b8;
+    s20 = a9 * b11 + a10 * b10 + a11 * b9;
+    s21 = a10 * b11 + a11 * b10;
+    s22 = a11 * b11;
+    s23 = 0;
+
+    carry0 = (s0 + (1 << 20)) >> 21;
+    s1 += carry0;
+    s0 -= carry0 << 21;
+    carry2 = (s2 + (1 << 20)) >> 21;
+    s3 += carry2;
+    s2 -= carry2 << 21;
+    carry4 = (s4 + (1 << 20)) >> 21;
+    s5 += carry4;
+    s4 -= carry4 << 21;
+    carry6 = (s6 + (1 << 20)) >> 21;
+    s7 += carry6;
+    s6 -= carry6 << 21;
+    carry8 = (s8 + (1 << 20)) >> 21;
+    s9 += carry8;
+    s8 -= carry8 << 21;
+    carry10 = (s10 + (1 << 20)) >> 21;
+    s11 += carry10;
+    s10 -= carry10 << 21;
+    carry12 = (s12 + (1 << 20)) >> 21;
+    s13 += carry12;
+    s12 -= carry12 << 21;
+    carry14 = (s14 + (1 << 20)) >> 21;
+    s15 += carry14;
+    s14 -= carry14 << 21;
+    carry16 = (s16 + (1 << 20)) >> 21;
+    s17 += carry16;
+    s16 -= carry16 << 21;
+    carry18 = (s18 + (1 << 20)) >> 21;
+    s19 += carry18;
+    s18 -= carry18 << 21;
+    carry20 = (s20 + (1 << 20)) >> 21;
+    s21 += carry20;
+    s20 -= carry20 << 21;
+    carry22 = (s22 + (1 << 20)) >> 21;
+    s23 += carry22;
+    s22 -= carry22 << 21;
+
+    carry1 = (s1 + (1 << 20)) >> 21;
+    s2 += carry1;
+    s1 -= carry1 << 21;
+    carry3 = (s3 + (1 << 20)) >> 21;
+    s4 += carry3;
+    s3 -= carry3 << 21;
+    carry5 = (s5 + (1 << 20)) >> 21;
+    s6 += carry5;
+    s5 -= carry5 << 21;
+    carry7 = (s7 + (1 << 20)) >> 21;
+    s8 += carry7;
+    s7 -= carry7 << 21;
+    carry9 = (s9 + (1 << 20)) >> 21;
+    s10 += carry9;
+    s9 -= carry9 << 21;
+    carry11 = (s11 + (1 << 20)) >> 21;
+    s12 += carry11;
+    s11 -= carry11 << 21;
+    carry13 = (s13 + (1 << 20)) >> 21;
+    s14 += carry13;
+    s13 -= carry13 << 21;
+    carry15 = (s15 + (1 << 20)) >> 21;
+    s16 += carry15;
+    s15 -= carry15 << 21;
+    carry17 = (s17 + (1 << 20)) >> 21;
+    s18 += carry17;
+    s17 -= carry17 << 21;
+    carry19 = (s19 + (1 << 20)) >> 21;
+    s20 += carry19;
+    s19 -= carry19 << 21;
+    carry21 = (s21 + (1 << 20)) >> 21;
+    s22 += carry21;
+    s21 -= carry21 << 21;
+
+    s11 += s23 * 666_643;
+    s12 += s23 * 470_296;
+    s13 += s23 * 654_183;
+    s14 -= s23 * 997_805;
+    s15 += s23 * 136_657;
+    s16 -= s23 * 683_901;
+
+    s10 += s22 * 666_643;
+    s11 += s22 * 470_296;
+    s12 += s22 * 654_183;
+    s13 -= s22 * 997_805;
+    s14 += s22 * 136_657;
+    s15 -= s22 * 683_901;
+
+    s9 += s21 * 666_643;
+    s10 += s21 * 470_296;
+    s11 += s21 * 654_183;
+    s12 -= s21 * 997_805;
+    s13 += s21 * 136_657;
+    s14 -= s21 * 683_901;
+
+    s8 += s20 * 666_643;
+    s9 += s20 * 470_296;
+    s10 += s20 * 654_183;
+    s11 -= s20 * 997_805;
+    s12 += s20 * 136_657;
+    s13 -= s20 * 683_901;
+
+    s7 += s19 * 666_643;
+    s8 += s19 * 470_296;
+    s9 += s19 * 654_183;
+    s10 -= s19 * 997_805;
+    s11 += s19 * 136_657;
+    s12 -= s19 * 683_901;
+
+    s6 += s18 * 666_643;
+    s7 += s18 * 470_296;
+    s8 += s18 * 654_183;
+    s9 -= s18 * 997_805;
+    s10 += s18 * 136_657;
+    s11 -= s18 * 683_901;
+
+    carry6 = (s6 + (1 << 20)) >> 21;
+    s7 += carry6;
+    s6 -= carry6 << 21;
+    carry8 = (s8 + (1 << 20)) >> 21;
+    s9 += carry8;
+    s8 -= carry8 << 21;
+    carry10 = (s10 + (1 << 20)) >> 21;
+    s11 += carry10;
+    s10 -= carry10 << 21;
+    carry12 = (s12 + (1 << 20)) >> 21;
+    s13 += carry12;
+    s12 -= carry12 << 21;
+    carry14 = (s14 + (1 << 20)) >> 21;
+    s15 += carry14;
+    s14 -= carry14 << 21;
+    carry16 = (s16 + (1 << 20)) >> 21;
+    s17 += carry16;
+    s16 -= carry16 << 21;
+
+    carry7 = (s7 + (1 << 20)) >> 21;
+    s8 += carry7;
+    s7 -= carry7 << 21;
+    carry9 = (s9 + (1 << 20)) >> 21;
+    s10 += carry9;
+    s9 -= carry9 << 21;
+    carry11 = (s11 + (1 << 20)) >> 21;
+    s12 += carry11;
+    s11 -= carry11 << 21;
+    carry13 = (s13 + (1 << 20)) >> 21;
+    s14 += carry13;
+    s13 -= carry13 << 21;
+    carry15 = (s15 + (1 << 20)) >> 21;
+    s16 += carry15;
+    s15 -= carry15 << 21;
+
+    s5 += s17 * 666_643;
+    s6 += s17 * 470_296;
+    s7 += s17 * 654_183;
+    s8 -= s17 * 997_805;
+    s9 += s17 * 136_657;
+    s10 -= s17 * 683_901;
+
+    s4 += s16 * 666_643;
+    s5 += s16 * 470_296;
+    s6 += s16 * 654_183;
+    s7 -= s16 * 997_805;
+    s8 += s16 * 136_657;
+    s9 -= s16 * 683_901;
+
+    s3 += s15 * 666_643;
+    s4 += s15 * 470_296;
+    s5 += s15 * 654_183;
+    s6 -= s15 * 997_805;
+    s7 += s15 * 136_657;
+    s8 -= s15 * 683_901;
+
+    s2 += s14 * 666_643;
+    s3 += s14 * 470_296;
+    s4 += s14 * 654_183;
+    s5 -= s14 * 997_805;
+    s6 += s14 * 136_657;
+    s7 -= s14 * 683_901;
+
+    s1 += s13 * 666_643;
+    s2 += s13 * 470_296;
+    s3 += s13 * 654_183;
+    s4 -= s13 * 997_805;
+    s5 += s13 * 136_657;
+    s6 -= s13 * 683_901;
+
+    s0 += s12 * 666_643;
+    s1 += s12 * 470_296;
+    s2 += s12 * 654_183;
+    s3 -= s12 * 997_805;
+    s4 += s12 * 136_657;
+    s5 -= s12 * 683_901;
+    s12 = 0;
+
+    carry0 = (s0 + (1 << 20)) >> 21;
+    s1 += carry0;
+    s0 -= carry0 << 21;
+    carry2 = (s2 + (1 << 20)) >> 21;
+    s3 += carry2;
+    s2 -= carry2 << 21;
+    carry4 = (s4 + (1 << 20)) >> 21;
+    s5 += carry4;
+    s4 -= carry4 << 21;
+    carry6 = (s6 + (1 << 20)) >> 21;
+    s7 += carry6;
+    s6 -= carry6 << 21;
+    carry8 = (s8 + (1 << 20)) >> 21;
+    s9 += carry8;
+    s8 -= carry8 << 21;
+    carry10 = (s10 + (1 << 20)) >> 21;
+    s11 += carry10;
+    s10 -= carry10 << 21;
+
+    carry1 = (s1 + (1 << 20)) >> 21;
+    s2 += carry1;
+    s1 -= carry1 << 21;
+    carry3 = (s3 + (1 << 20)) >> 21;
+    s4 += carry3;
+    s3 -= carry3 << 21;
+    carry5 = (s5 + (1 << 20)) >> 21;
+    s6 += carry5;
+    s5 -= carry5 << 21;
+    carry7 = (s7 + (1 << 20)) >> 21;
+    s8 += carry7;
+    s7 -= carry7 << 21;
+    carry9 = (s9 + (1 << 20)) >> 21;
+    s10 += carry9;
+    s9 -= carry9 << 21;
+    carry11 = (s11 + (1 << 20)) >> 21;
+    s12 += carry11;
+    s11 -= carry11 << 21;
+
+    s0 += s12 * 666_643;
+    s1 += s12 * 470_296;
+    s2 += s12 * 654_183;
+    s3 -= s12 * 997_805;
+    s4 += s12 * 136_657;
+    s5 -= s12 * 683_901;
+    s12 = 0;
+
+    carry0 = s0 >> 21;
+    s1 += carry0;
+    s0 -= carry0 << 21;
+    carry1 = s1 >> 21;
+    s2 += carry1;
+    s1 -= carry1 << 21;
+    carry2 = s2 >> 21;
+    s3 += carry2;
+    s2 -= carry2 << 21;
+    carry3 = s3 >> 21;
+    s4 += carry3;
+    s3 -= carry3 << 21;
+    carry4 = s4 >> 21;
+    s5 += carry4;
+    s4 -= carry4 << 21;
+    carry5 = s5 >> 21;
+    s6 += carry5;
+    s5 -= carry5 << 21;
+    carry6 = s6 >> 21;
+    s7 += carry6;
+    s6 -= carry6 << 21;
+    carry7 = s7 >> 21;
+    s8 += carry7;
+    s7 -= carry7 << 21;
+    carry8 = s8 >> 21;
+    s9 += carry8;
+    s8 -= carry8 << 21;
+    carry9 = s9 >> 21;
+    s10 += carry9;
+    s9 -= carry9 << 21;
+    carry10 = s10 >> 21;
+    s11 += carry10;
+    s10 -= carry10 << 21;
+    carry11 = s11 >> 21;
+    s12 += carry11;
+    s11 -= carry11 << 21;
+
+    s0 += s12 * 666_643;
+    s1 += s12 * 470_296;
+    s2 += s12 * 654_183;
+    s3 -= s12 * 997_805;
+    s4 += s12 * 136_657;
+    s5 -= s12 * 683_901;
+
+    carry0 = s0 >> 21;
+    s1 += carry0;
+    s0 -= carry0 << 21;
+    carry1 = s1 >> 21;
+    s2 += carry1;
+    s1 -= carry1 << 21;
+    carry2 = s2 >> 21;
+    s3 += carry2;
+    s2 -= carry2 << 21;
+    carry3 = s3 >> 21;
+    s4 += carry3;
+    s3 -= carry3 << 21;
+    carry4 = s4 >> 21;
+    s5 += carry4;
+    s4 -= carry4 << 21;
+    carry5 = s5 >> 21;
+    s6 += carry5;
+    s5 -= carry5 << 21;
+    carry6 = s6 >> 21;
+    s7 += carry6;
+    s6 -= carry6 << 21;
+    carry7 = s7 >> 21;
+    s8 += carry7;
+    s7 -= carry7 << 21;
+    carry8 = s8 >> 21;
+    s9 += carry8;
+    s8 -= carry8 << 21;
+    carry9 = s9 >> 21;
+    s10 += carry9;
+    s9 -= carry9 << 21;
+    carry10 = s10 >> 21;
+    s11 += carry10;
+    s10 -= carry10 << 21;
+
+    s[0] = s0 as u8;
+    s[1] = (s0 >> 8) as u8;
+    s[2] = ((s0 >> 16) | (s1 << 5)) as u8;
+    s[3] = (s1 >> 3) as u8;
+    s[4] = (s1 >> 11) as u8;
+    s[5] = ((s1 >> 19) | (s2 << 2)) as u8;
+    s[6] = (s2 >> 6) as u8;
+    s[7] = ((s2 >> 14) | (s3 << 7)) as u8;
+    s[8] = (s3 >> 1) as u8;
+    s[9] = (s3 >> 9) as u8;
+    s[10] = ((s3 >> 17) | (s4 << 4)) as u8;
+    s[11] = (s4 >> 4) as u8;
+    s[12] = (s4 >> 12) as u8;
+    s[13] = ((s4 >> 20) | (s5 << 1)) as u8;
+    s[14] = (s5 >> 7) as u8;
+    s[15] = ((s5 >> 15) | (s6 << 6)) as u8;
+    s[16] = (s6 >> 2) as u8;
+    s[17] = (s6 >> 10) as u8;
+    s[18] = ((s6 >> 18) | (s7 << 3)) as u8;
+    s[19] = (s7 >> 5) as u8;
+    s[20] = (s7 >> 13) as u8;
+    s[21] = s8 as u8;
+    s[22] = (s8 >> 8) as u8;
+    s[23] = ((s8 >> 16) | (s9 << 5)) as u8;
+    s[24] = (s9 >> 3) as u8;
+    s[25] = (s9 >> 11) as u8;
+    s[26] = ((s9 >> 19) | (s10 << 2)) as u8;
+    s[27] = (s10 >> 6) as u8;
+    s[28] = ((s10 >> 14) | (s11 << 7)) as u8;
+    s[29] = (s11 >> 1) as u8;
+    s[30] = (s11 >> 9) as u8;
+    s[31] = (s11 >> 17) as u8;
+}
@@ -2,45 +2,132 @@
 #![allow(
     clippy::suspicious_arithmetic_impl,
     clippy::many_single_char_names,
-    clippy::unknown_clippy_lints
+    clippy::needless_late_init,
+    clippy::wrong_self_convention,
+    unknown_lints
 )]
 #![no_std]
+// The `portable` feature swaps out the asm comparison, the volatile writes
+// of zeroize and the AVX2 backend for safe code, so that nothing in the
+// crate needs unsafe. This makes any unsafe left over a compile error.
+#![cfg_attr(feature = "portable", forbid(unsafe_code))]
 
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(all(
+    feature = "ct-test",
+    target_arch = "x86_64",
+    not(feature = "portable")
+))]
+pub mod ct_test;
+pub mod edwards;
+mod elligator;
+mod error;
+#[cfg(all(
+    feature = "simd",
+    target_arch = "x86_64",
+    not(feature = "u64_backend"),
+    not(feature = "portable")
+))]
+mod field_avx2;
+#[cfg(not(feature = "u64_backend"))]
+mod field_u32;
+#[cfg(feature = "u64_backend")]
+mod field_u64;
+#[cfg(feature = "fuzzing")]
+mod fuzzing;
+#[cfg(feature = "hash_to_curve")]
+mod hash_to_curve;
+pub mod hazmat;
+pub mod ristretto;
+mod scalar;
+#[cfg(feature = "serde")]
+mod serialization;
 mod statics;
 mod util;
+#[cfg(feature = "vrf")]
+pub mod vrf;
+mod x25519;
+
+#[cfg(not(feature = "u64_backend"))]
+pub use crate::field_u32::FieldElement;
+#[cfg(feature = "u64_backend")]
+pub use crate::field_u64::FieldElement;
+#[cfg(feature = "fuzzing")]
+pub use crate::fuzzing::fuzz_decompress_roundtrip;
+#[cfg(feature = "hash_to_curve")]
+pub use crate::hash_to_curve::hash_to_edwards;
+pub use crate::{
+    elligator::{elligator2_inverse, elligator2_map},
+    error::Error,
+    scalar::Scalar,
+    statics::{FIELD_MODULUS, GROUP_ORDER},
+    x25519::{X25519PublicKey, X25519SecretKey, X25519SharedSecret},
+};
+
 use crate::{
-    statics::{BI, FE_D, FE_D2, FE_ONE, FE_SQRTM1, FE_ZERO, GE_PRECOMP_BASE},
-    util::fixed_time_eq,
+    hazmat::{ge_scalarmult_base, sc_muladd, sc_reduce, GeCached, GeP2, GeP3},
+    statics::{FE_ONE, FE_SQRTM1, FE_ZERO},
+    util::{ct_is_zero, fixed_time_eq, zeroize_hardened},
 };
 use core::{
-    cmp::{min, Eq, PartialEq},
-    ops::{Add, Mul, Sub},
+    cmp::{Eq, PartialEq},
+    convert::TryFrom,
+    ops::{Add, Mul, Neg, Sub},
+    slice,
 };
 
-#[allow(unused_imports)]
-use rand::{Error as RndError, ErrorKind::Unavailable, Rng};
-
-#[cfg(feature = "std")]
-use rand::rngs::OsRng;
-
-/// Here the field is \Z/(2^255-19).
-///
-/// An element t, entries t\[0\]...t\[9\], represents the integer
-/// `t[0]+2^26 t[1]+2^51 t[2]+2^77 t[3]+2^102 t[4]+...+2^230 t[9]`.
-/// Bounds on each t\[i\] vary depending on context.
-#[derive(Clone, Copy)]
-pub struct FieldElement(pub [i32; 10]);
+use rand_core::{CryptoRng, RngCore};
 
+// Limbs aren't unique across backends (or even across carry chains), so
+// compare the canonical encodings instead.
 impl PartialEq for FieldElement {
     fn eq(&self, other: &FieldElement) -> bool {
-        let &FieldElement(self_elems) = self;
-        let &FieldElement(other_elems) = other;
-        self_elems == other_elems
+        fixed_time_eq(&self.to_bytes(), &other.to_bytes())
     }
 }
 
 impl Eq for FieldElement {}
 
+impl Default for FieldElement {
+    /// Zero, like the integer types.
+    fn default() -> FieldElement { FE_ZERO }
+}
+
+// `&f op &g`, forwarding to the by-value operators of the backend.
+macro_rules! field_ref_binop {
+    ($trait:ident, $method:ident) => {
+        impl<'a, 'b> $trait<&'b FieldElement> for &'a FieldElement {
+            type Output = FieldElement;
+
+            #[inline]
+            fn $method(self, rhs: &'b FieldElement) -> FieldElement {
+                $trait::$method(*self, *rhs)
+            }
+        }
+    };
+}
+
+field_ref_binop!(Add, add);
+field_ref_binop!(Sub, sub);
+field_ref_binop!(Mul, mul);
+
+// The inherent `neg` of the backend, as an operator.
+impl Neg for FieldElement {
+    type Output = FieldElement;
+
+    #[inline]
+    fn neg(self) -> FieldElement { FieldElement::neg(&self) }
+}
+
+impl Neg for &FieldElement {
+    type Output = FieldElement;
+
+    #[inline]
+    fn neg(self) -> FieldElement { FieldElement::neg(self) }
+}
+
 #[inline]
 fn load_4u(s: &[u8]) -> u64 {
     u64::from(s[0])
@@ -60,1023 +147,110 @@ fn load_3u(s: &[u8]) -> u64 {
 #[inline]
 fn load_3i(s: &[u8]) -> i64 { load_3u(s) as i64 }
 
-impl Add for FieldElement {
-    type Output = FieldElement;
-
-    // `h = f + g`
-    // Can overlap `h` with `f` or `g`.
-    //
-    // Preconditions:
-    //    |f| bounded by 1.1*2^25,1.1*2^24,1.1*2^25,1.1*2^24,etc.
-    //    |g| bounded by 1.1*2^25,1.1*2^24,1.1*2^25,1.1*2^24,etc.
-    //
-    // Postconditions:
-    //    |h| bounded by 1.1*2^26,1.1*2^25,1.1*2^26,1.1*2^25,etc.
-    fn add(self, rhs: FieldElement) -> FieldElement {
-        let FieldElement(f) = self;
-        let FieldElement(g) = rhs;
-        let [f0, f1, f2, f3, f4, f5, f6, f7, f8, f9] = f;
-        let [g0, g1, g2, g3, g4, g5, g6, g7, g8, g9] = g;
-        let h0 = f0 + g0;
-        let h1 = f1 + g1;
-        let h2 = f2 + g2;
-        let h3 = f3 + g3;
-        let h4 = f4 + g4;
-        let h5 = f5 + g5;
-        let h6 = f6 + g6;
-        let h7 = f7 + g7;
-        let h8 = f8 + g8;
-        let h9 = f9 + g9;
-        FieldElement([h0, h1, h2, h3, h4, h5, h6, h7, h8, h9])
+// |t[i]| <= 1.1*2^26 for even i and 1.1*2^25 for odd i, the input bounds
+// of Mul for 10-limb elements.
+const fn limbs_in_bounds(limbs: &[i32; 10]) -> bool {
+    let mut i = 0;
+    while i < 10 {
+        let bits = if i % 2 == 0 { 26 } else { 25 };
+        let bound = (11i64 << bits) / 10;
+        let limb = limbs[i] as i64;
+        if limb > bound || limb < -bound {
+            return false;
+        }
+        i += 1;
     }
+    true
 }
 
-impl Sub for FieldElement {
-    type Output = FieldElement;
-
-    // `h = f - g`
-    // Can overlap `h` with `f` or `g`.
-    //
-    // Preconditions:
-    //    |f| bounded by 1.1*2^25,1.1*2^24,1.1*2^25,1.1*2^24,etc.
-    //    |g| bounded by 1.1*2^25,1.1*2^24,1.1*2^25,1.1*2^24,etc.
-    //
-    // Postconditions:
-    //    |h| bounded by 1.1*2^26,1.1*2^25,1.1*2^26,1.1*2^25,etc.
-    fn sub(self, rhs: FieldElement) -> FieldElement {
-        let FieldElement(f) = self;
-        let FieldElement(g) = rhs;
-
-        let [f0, f1, f2, f3, f4, f5, f6, f7, f8, f9] = f;
-        let [g0, g1, g2, g3, g4, g5, g6, g7, g8, g9] = g;
-        let h0 = f0 - g0;
-        let h1 = f1 - g1;
-        let h2 = f2 - g2;
-        let h3 = f3 - g3;
-        let h4 = f4 - g4;
-        let h5 = f5 - g5;
-        let h6 = f6 - g6;
-        let h7 = f7 - g7;
-        let h8 = f8 - g8;
-        let h9 = f9 - g9;
-        FieldElement([h0, h1, h2, h3, h4, h5, h6, h7, h8, h9])
+impl FieldElement {
+    /// The field element 0.
+    pub fn zero() -> FieldElement { FE_ZERO }
+
+    /// The field element 1.
+    pub fn one() -> FieldElement { FE_ONE }
+
+    /// Like [`from_limbs`](FieldElement::from_limbs), but debug builds
+    /// panic unless every limb is within the bounds the arithmetic
+    /// expects: `1.1*2^26` in absolute value for even limbs and `1.1*2^25`
+    /// for odd ones. Release builds don't check.
+    pub const fn from_limbs_checked(limbs: [i32; 10]) -> FieldElement {
+        debug_assert!(limbs_in_bounds(&limbs), "limb out of bounds");
+        FieldElement::from_limbs(limbs)
     }
-}
-
-impl Mul for FieldElement {
-    type Output = FieldElement;
 
-    // `h = f * g`
-    // Can overlap h with `f` or `g`.
-    //
-    // Preconditions:
-    //    |f| bounded by 1.1*2^26,1.1*2^25,1.1*2^26,1.1*2^25,etc.
-    //    |g| bounded by 1.1*2^26,1.1*2^25,1.1*2^26,1.1*2^25,etc.
-    //
-    // Postconditions:
-    //    |h| bounded by 1.1*2^25,1.1*2^24,1.1*2^25,1.1*2^24,etc.
-    //
-    // Notes on implementation strategy:
-    //
-    // Using schoolbook multiplication.
-    // Karatsuba would save a little in some cost models.
-    //
-    // Most multiplications by 2 and 19 are 32-bit precomputations;
-    // cheaper than 64-bit postcomputations.
-    //
-    // There is one remaining multiplication by 19 in the carry chain;
-    // one *19 precomputation can be merged into this,
-    // but the resulting data flow is considerably less clean.
-    //
-    // There are 12 carries below.
-    // 10 of them are 2-way parallelizable and vectorizable.
-    // Can get away with 11 carries, but then data flow is much deeper.
-    //
-    // With tighter constraints on inputs can squeeze carries into int32.
-    fn mul(self, rhs: FieldElement) -> FieldElement {
-        let FieldElement(f) = self;
-        let FieldElement(g) = rhs;
-        let [f0, f1, f2, f3, f4, f5, f6, f7, f8, f9] = f;
-        let [g0, g1, g2, g3, g4, g5, g6, g7, g8, g9] = g;
-
-        let g1_19 = 19 * g1; // 1.4*2^29
-        let g2_19 = 19 * g2; // 1.4*2^30; still ok
-        let g3_19 = 19 * g3;
-        let g4_19 = 19 * g4;
-        let g5_19 = 19 * g5;
-        let g6_19 = 19 * g6;
-        let g7_19 = 19 * g7;
-        let g8_19 = 19 * g8;
-        let g9_19 = 19 * g9;
-        let f1_2 = 2 * f1;
-        let f3_2 = 2 * f3;
-        let f5_2 = 2 * f5;
-        let f7_2 = 2 * f7;
-        let f9_2 = 2 * f9;
-        let f0g0 = i64::from(f0) * i64::from(g0);
-        let f0g1 = i64::from(f0) * i64::from(g1);
-        let f0g2 = i64::from(f0) * i64::from(g2);
-        let f0g3 = i64::from(f0) * i64::from(g3);
-        let f0g4 = i64::from(f0) * i64::from(g4);
-        let f0g5 = i64::from(f0) * i64::from(g5);
-        let f0g6 = i64::from(f0) * i64::from(g6);
-        let f0g7 = i64::from(f0) * i64::from(g7);
-        let f0g8 = i64::from(f0) * i64::from(g8);
-        let f0g9 = i64::from(f0) * i64::from(g9);
-        let f1g0 = i64::from(f1) * i64::from(g0);
-        let f1g1_2 = i64::from(f1_2) * i64::from(g1);
-        let f1g2 = i64::from(f1) * i64::from(g2);
-        let f1g3_2 = i64::from(f1_2) * i64::from(g3);
-        let f1g4 = i64::from(f1) * i64::from(g4);
-        let f1g5_2 = i64::from(f1_2) * i64::from(g5);
-        let f1g6 = i64::from(f1) * i64::from(g6);
-        let f1g7_2 = i64::from(f1_2) * i64::from(g7);
-        let f1g8 = i64::from(f1) * i64::from(g8);
-        let f1g9_38 = i64::from(f1_2) * i64::from(g9_19);
-        let f2g0 = i64::from(f2) * i64::from(g0);
-        let f2g1 = i64::from(f2) * i64::from(g1);
-        let f2g2 = i64::from(f2) * i64::from(g2);
-        let f2g3 = i64::from(f2) * i64::from(g3);
-        let f2g4 = i64::from(f2) * i64::from(g4);
-        let f2g5 = i64::from(f2) * i64::from(g5);
-        let f2g6 = i64::from(f2) * i64::from(g6);
-        let f2g7 = i64::from(f2) * i64::from(g7);
-        let f2g8_19 = i64::from(f2) * i64::from(g8_19);
-        let f2g9_19 = i64::from(f2) * i64::from(g9_19);
-        let f3g0 = i64::from(f3) * i64::from(g0);
-        let f3g1_2 = i64::from(f3_2) * i64::from(g1);
-        let f3g2 = i64::from(f3) * i64::from(g2);
-        let f3g3_2 = i64::from(f3_2) * i64::from(g3);
-        let f3g4 = i64::from(f3) * i64::from(g4);
-        let f3g5_2 = i64::from(f3_2) * i64::from(g5);
-        let f3g6 = i64::from(f3) * i64::from(g6);
-        let f3g7_38 = i64::from(f3_2) * i64::from(g7_19);
-        let f3g8_19 = i64::from(f3) * i64::from(g8_19);
-        let f3g9_38 = i64::from(f3_2) * i64::from(g9_19);
-        let f4g0 = i64::from(f4) * i64::from(g0);
-        let f4g1 = i64::from(f4) * i64::from(g1);
-        let f4g2 = i64::from(f4) * i64::from(g2);
-        let f4g3 = i64::from(f4) * i64::from(g3);
-        let f4g4 = i64::from(f4) * i64::from(g4);
-        let f4g5 = i64::from(f4) * i64::from(g5);
-        let f4g6_19 = i64::from(f4) * i64::from(g6_19);
-        let f4g7_19 = i64::from(f4) * i64::from(g7_19);
-        let f4g8_19 = i64::from(f4) * i64::from(g8_19);
-        let f4g9_19 = i64::from(f4) * i64::from(g9_19);
-        let f5g0 = i64::from(f5) * i64::from(g0);
-        let f5g1_2 = i64::from(f5_2) * i64::from(g1);
-        let f5g2 = i64::from(f5) * i64::from(g2);
-        let f5g3_2 = i64::from(f5_2) * i64::from(g3);
-        let f5g4 = i64::from(f5) * i64::from(g4);
-        let f5g5_38 = i64::from(f5_2) * i64::from(g5_19);
-        let f5g6_19 = i64::from(f5) * i64::from(g6_19);
-        let f5g7_38 = i64::from(f5_2) * i64::from(g7_19);
-        let f5g8_19 = i64::from(f5) * i64::from(g8_19);
-        let f5g9_38 = i64::from(f5_2) * i64::from(g9_19);
-        let f6g0 = i64::from(f6) * i64::from(g0);
-        let f6g1 = i64::from(f6) * i64::from(g1);
-        let f6g2 = i64::from(f6) * i64::from(g2);
-        let f6g3 = i64::from(f6) * i64::from(g3);
-        let f6g4_19 = i64::from(f6) * i64::from(g4_19);
-        let f6g5_19 = i64::from(f6) * i64::from(g5_19);
-        let f6g6_19 = i64::from(f6) * i64::from(g6_19);
-        let f6g7_19 = i64::from(f6) * i64::from(g7_19);
-        let f6g8_19 = i64::from(f6) * i64::from(g8_19);
-        let f6g9_19 = i64::from(f6) * i64::from(g9_19);
-        let f7g0 = i64::from(f7) * i64::from(g0);
-        let f7g1_2 = i64::from(f7_2) * i64::from(g1);
-        let f7g2 = i64::from(f7) * i64::from(g2);
-        let f7g3_38 = i64::from(f7_2) * i64::from(g3_19);
-        let f7g4_19 = i64::from(f7) * i64::from(g4_19);
-        let f7g5_38 = i64::from(f7_2) * i64::from(g5_19);
-        let f7g6_19 = i64::from(f7) * i64::from(g6_19);
-        let f7g7_38 = i64::from(f7_2) * i64::from(g7_19);
-        let f7g8_19 = i64::from(f7) * i64::from(g8_19);
-        let f7g9_38 = i64::from(f7_2) * i64::from(g9_19);
-        let f8g0 = i64::from(f8) * i64::from(g0);
-        let f8g1 = i64::from(f8) * i64::from(g1);
-        let f8g2_19 = i64::from(f8) * i64::from(g2_19);
-        let f8g3_19 = i64::from(f8) * i64::from(g3_19);
-        let f8g4_19 = i64::from(f8) * i64::from(g4_19);
-        let f8g5_19 = i64::from(f8) * i64::from(g5_19);
-        let f8g6_19 = i64::from(f8) * i64::from(g6_19);
-        let f8g7_19 = i64::from(f8) * i64::from(g7_19);
-        let f8g8_19 = i64::from(f8) * i64::from(g8_19);
-        let f8g9_19 = i64::from(f8) * i64::from(g9_19);
-        let f9g0 = i64::from(f9) * i64::from(g0);
-        let f9g1_38 = i64::from(f9_2) * i64::from(g1_19);
-        let f9g2_19 = i64::from(f9) * i64::from(g2_19);
-        let f9g3_38 = i64::from(f9_2) * i64::from(g3_19);
-        let f9g4_19 = i64::from(f9) * i64::from(g4_19);
-        let f9g5_38 = i64::from(f9_2) * i64::from(g5_19);
-        let f9g6_19 = i64::from(f9) * i64::from(g6_19);
-        let f9g7_38 = i64::from(f9_2) * i64::from(g7_19);
-        let f9g8_19 = i64::from(f9) * i64::from(g8_19);
-        let f9g9_38 = i64::from(f9_2) * i64::from(g9_19);
-        let mut h0 = f0g0
-            + f1g9_38
-            + f2g8_19
-            + f3g7_38
-            + f4g6_19
-            + f5g5_38
-            + f6g4_19
-            + f7g3_38
-            + f8g2_19
-            + f9g1_38;
-        let mut h1 = f0g1
-            + f1g0
-            + f2g9_19
-            + f3g8_19
-            + f4g7_19
-            + f5g6_19
-            + f6g5_19
-            + f7g4_19
-            + f8g3_19
-            + f9g2_19;
-        let mut h2 = f0g2
-            + f1g1_2
-            + f2g0
-            + f3g9_38
-            + f4g8_19
-            + f5g7_38
-            + f6g6_19
-            + f7g5_38
-            + f8g4_19
-            + f9g3_38;
-        let mut h3 = f0g3
-            + f1g2
-            + f2g1
-            + f3g0
-            + f4g9_19
-            + f5g8_19
-            + f6g7_19
-            + f7g6_19
-            + f8g5_19
-            + f9g4_19;
-        let mut h4 = f0g4
-            + f1g3_2
-            + f2g2
-            + f3g1_2
-            + f4g0
-            + f5g9_38
-            + f6g8_19
-            + f7g7_38
-            + f8g6_19
-            + f9g5_38;
-        let mut h5 = f0g5
-            + f1g4
-            + f2g3
-            + f3g2
-            + f4g1
-            + f5g0
-            + f6g9_19
-            + f7g8_19
-            + f8g7_19
-            + f9g6_19;
-        let mut h6 = f0g6
-            + f1g5_2
-            + f2g4
-            + f3g3_2
-            + f4g2
-            + f5g1_2
-            + f6g0
-            + f7g9_38
-            + f8g8_19
-            + f9g7_38;
-        let mut h7 = f0g7
-            + f1g6
-            + f2g5
-            + f3g4
-            + f4g3
-            + f5g2
-            + f6g1
-            + f7g0
-            + f8g9_19
-            + f9g8_19;
-        let mut h8 = f0g8
-            + f1g7_2
-            + f2g6
-            + f3g5_2
-            + f4g4
-            + f5g3_2
-            + f6g2
-            + f7g1_2
-            + f8g0
-            + f9g9_38;
-        let mut h9 =
-            f0g9 + f1g8 + f2g7 + f3g6 + f4g5 + f5g4 + f6g3 + f7g2 + f8g1 + f9g0;
-        let mut carry0;
-        let carry1;
-        let carry2;
-        let carry3;
-        let mut carry4;
-        let carry5;
-        let carry6;
-        let carry7;
-        let carry8;
-        let carry9;
-
-        // |h0| <= (1.1*1.1*2^52*(1+19+19+19+19)+1.1*1.1*2^50*(38+38+38+38+38))
-        //   i.e. |h0| <= 1.2*2^59; narrower ranges for h2, h4, h6, h8
-        // |h1| <= (1.1*1.1*2^51*(1+1+19+19+19+19+19+19+19+19))
-        //   i.e. |h1| <= 1.5*2^58; narrower ranges for h3, h5, h7, h9
-
-        carry0 = (h0 + (1 << 25)) >> 26;
-        h1 += carry0;
-        h0 -= carry0 << 26;
-        carry4 = (h4 + (1 << 25)) >> 26;
-        h5 += carry4;
-        h4 -= carry4 << 26;
-        // |h0| <= 2^25
-        // |h4| <= 2^25
-        // |h1| <= 1.51*2^58
-        // |h5| <= 1.51*2^58
-
-        carry1 = (h1 + (1 << 24)) >> 25;
-        h2 += carry1;
-        h1 -= carry1 << 25;
-        carry5 = (h5 + (1 << 24)) >> 25;
-        h6 += carry5;
-        h5 -= carry5 << 25;
-        // |h1| <= 2^24; from now on fits into int32
-        // |h5| <= 2^24; from now on fits into int32
-        // |h2| <= 1.21*2^59
-        // |h6| <= 1.21*2^59
-
-        carry2 = (h2 + (1 << 25)) >> 26;
-        h3 += carry2;
-        h2 -= carry2 << 26;
-        carry6 = (h6 + (1 << 25)) >> 26;
-        h7 += carry6;
-        h6 -= carry6 << 26;
-        // |h2| <= 2^25; from now on fits into int32 unchanged
-        // |h6| <= 2^25; from now on fits into int32 unchanged
-        // |h3| <= 1.51*2^58
-        // |h7| <= 1.51*2^58
-
-        carry3 = (h3 + (1 << 24)) >> 25;
-        h4 += carry3;
-        h3 -= carry3 << 25;
-        carry7 = (h7 + (1 << 24)) >> 25;
-        h8 += carry7;
-        h7 -= carry7 << 25;
-        // |h3| <= 2^24; from now on fits into int32 unchanged
-        // |h7| <= 2^24; from now on fits into int32 unchanged
-        // |h4| <= 1.52*2^33
-        // |h8| <= 1.52*2^33
-
-        carry4 = (h4 + (1 << 25)) >> 26;
-        h5 += carry4;
-        h4 -= carry4 << 26;
-        carry8 = (h8 + (1 << 25)) >> 26;
-        h9 += carry8;
-        h8 -= carry8 << 26;
-        // |h4| <= 2^25; from now on fits into int32 unchanged
-        // |h8| <= 2^25; from now on fits into int32 unchanged
-        // |h5| <= 1.01*2^24
-        // |h9| <= 1.51*2^58
-
-        carry9 = (h9 + (1 << 24)) >> 25;
-        h0 += carry9 * 19;
-        h9 -= carry9 << 25;
-        // |h9| <= 2^24; from now on fits into int32 unchanged
-        // |h0| <= 1.8*2^37
-
-        carry0 = (h0 + (1 << 25)) >> 26;
-        h1 += carry0;
-        h0 -= carry0 << 26;
-        // |h0| <= 2^25; from now on fits into int32 unchanged
-        // |h1| <= 1.01*2^24
-
-        FieldElement([
-            h0 as i32, h1 as i32, h2 as i32, h3 as i32, h4 as i32, h5 as i32,
-            h6 as i32, h7 as i32, h8 as i32, h9 as i32,
-        ])
+    /// Like `from_bytes`, but for a slice of unknown length: returns `None`
+    /// unless `s` is exactly 32 bytes long.
+    pub fn try_from_bytes(s: &[u8]) -> Option<FieldElement> {
+        <&[u8; 32]>::try_from(s).ok().map(FieldElement::from_bytes)
     }
-}
 
-impl FieldElement {
-    pub fn from_bytes(s: &[u8]) -> FieldElement {
-        let mut h0 = load_4i(&s[0..4]);
-        let mut h1 = load_3i(&s[4..7]) << 6;
-        let mut h2 = load_3i(&s[7..10]) << 5;
-        let mut h3 = load_3i(&s[10..13]) << 3;
-        let mut h4 = load_3i(&s[13..16]) << 2;
-        let mut h5 = load_4i(&s[16..20]);
-        let mut h6 = load_3i(&s[20..23]) << 7;
-        let mut h7 = load_3i(&s[23..26]) << 5;
-        let mut h8 = load_3i(&s[26..29]) << 4;
-        let mut h9 = (load_3i(&s[29..32]) & 8_388_607) << 2;
-
-        let carry9 = (h9 + (1 << 24)) >> 25;
-        h0 += carry9 * 19;
-        h9 -= carry9 << 25;
-        let carry1 = (h1 + (1 << 24)) >> 25;
-        h2 += carry1;
-        h1 -= carry1 << 25;
-        let carry3 = (h3 + (1 << 24)) >> 25;
-        h4 += carry3;
-        h3 -= carry3 << 25;
-        let carry5 = (h5 + (1 << 24)) >> 25;
-        h6 += carry5;
-        h5 -= carry5 << 25;
-        let carry7 = (h7 + (1 << 24)) >> 25;
-        h8 += carry7;
-        h7 -= carry7 << 25;
-
-        let carry0 = (h0 + (1 << 25)) >> 26;
-        h1 += carry0;
-        h0 -= carry0 << 26;
-        let carry2 = (h2 + (1 << 25)) >> 26;
-        h3 += carry2;
-        h2 -= carry2 << 26;
-        let carry4 = (h4 + (1 << 25)) >> 26;
-        h5 += carry4;
-        h4 -= carry4 << 26;
-        let carry6 = (h6 + (1 << 25)) >> 26;
-        h7 += carry6;
-        h6 -= carry6 << 26;
-        let carry8 = (h8 + (1 << 25)) >> 26;
-        h9 += carry8;
-        h8 -= carry8 << 26;
-
-        FieldElement([
-            h0 as i32, h1 as i32, h2 as i32, h3 as i32, h4 as i32, h5 as i32,
-            h6 as i32, h7 as i32, h8 as i32, h9 as i32,
-        ])
-    }
-
-    // Preconditions:
-    //   |h| bounded by 1.1*2^25,1.1*2^24,1.1*2^25,1.1*2^24,etc.
-    //
-    // Write p=2^255-19; q=floor(h/p).
-    // Basic claim: q = floor(2^(-255)(h + 19 2^(-25)h9 + 2^(-1))).
-    //
-    // Proof:
-    //   Have |h|<=p so |q|<=1 so |19^2 2^(-255) q|<1/4.
-    //   Also have |h-2^230 h9|<2^230 so |19 2^(-255)(h-2^230 h9)|<1/4.
-    //
-    //   Write y=2^(-1)-19^2 2^(-255)q-19 2^(-255)(h-2^230 h9).
-    //   Then 0<y<1.
-    //
-    //   Write r=h-pq.
-    //   Have 0<=r<=p-1=2^255-20.
-    //   Thus 0<=r+19(2^-255)r<r+19(2^-255)2^255<=2^255-1.
-    //
-    //   Write x=r+19(2^-255)r+y.
-    //   Then 0<x<2^255 so floor(2^(-255)x) = 0 so floor(q+2^(-255)x) = q.
-    //
-    //   Have q+2^(-255)x = 2^(-255)(h + 19 2^(-25) h9 + 2^(-1))
-    //   so floor(2^(-255)(h + 19 2^(-25) h9 + 2^(-1))) = q.
-    pub fn to_bytes(&self) -> [u8; 32] {
-        let &FieldElement(es) = self;
-        let mut h0 = es[0];
-        let mut h1 = es[1];
-        let mut h2 = es[2];
-        let mut h3 = es[3];
-        let mut h4 = es[4];
-        let mut h5 = es[5];
-        let mut h6 = es[6];
-        let mut h7 = es[7];
-        let mut h8 = es[8];
-        let mut h9 = es[9];
-        let mut q;
-
-        q = (19 * h9 + (1 << 24)) >> 25;
-        q = (h0 + q) >> 26;
-        q = (h1 + q) >> 25;
-        q = (h2 + q) >> 26;
-        q = (h3 + q) >> 25;
-        q = (h4 + q) >> 26;
-        q = (h5 + q) >> 25;
-        q = (h6 + q) >> 26;
-        q = (h7 + q) >> 25;
-        q = (h8 + q) >> 26;
-        q = (h9 + q) >> 25;
-
-        // Goal: Output h-(2^255-19)q, which is between 0 and 2^255-20.
-        h0 += 19 * q;
-        // Goal: Output h-2^255 q, which is between 0 and 2^255-20.
-
-        let carry0 = h0 >> 26;
-        h1 += carry0;
-        h0 -= carry0 << 26;
-        let carry1 = h1 >> 25;
-        h2 += carry1;
-        h1 -= carry1 << 25;
-        let carry2 = h2 >> 26;
-        h3 += carry2;
-        h2 -= carry2 << 26;
-        let carry3 = h3 >> 25;
-        h4 += carry3;
-        h3 -= carry3 << 25;
-        let carry4 = h4 >> 26;
-        h5 += carry4;
-        h4 -= carry4 << 26;
-        let carry5 = h5 >> 25;
-        h6 += carry5;
-        h5 -= carry5 << 25;
-        let carry6 = h6 >> 26;
-        h7 += carry6;
-        h6 -= carry6 << 26;
-        let carry7 = h7 >> 25;
-        h8 += carry7;
-        h7 -= carry7 << 25;
-        let carry8 = h8 >> 26;
-        h9 += carry8;
-        h8 -= carry8 << 26;
-        let carry9 = h9 >> 25;
-        h9 -= carry9 << 25;
-        // h10 = carry9
-
-        // Goal: Output h0+...+2^255 h10-2^255 q, which is between 0 and
-        // 2^255-20. Have h0+...+2^230 h9 between 0 and 2^255-1;
-        // evidently 2^255 h10-2^255 q = 0.
-        // Goal: Output h0+...+2^230 h9.
-        [
-            h0 as u8,
-            (h0 >> 8) as u8,
-            (h0 >> 16) as u8,
-            ((h0 >> 24) | (h1 << 2)) as u8,
-            (h1 >> 6) as u8,
-            (h1 >> 14) as u8,
-            ((h1 >> 22) | (h2 << 3)) as u8,
-            (h2 >> 5) as u8,
-            (h2 >> 13) as u8,
-            ((h2 >> 21) | (h3 << 5)) as u8,
-            (h3 >> 3) as u8,
-            (h3 >> 11) as u8,
-            ((h3 >> 19) | (h4 << 6)) as u8,
-            (h4 >> 2) as u8,
-            (h4 >> 10) as u8,
-            (h4 >> 18) as u8,
-            h5 as u8,
-            (h5 >> 8) as u8,
-            (h5 >> 16) as u8,
-            ((h5 >> 24) | (h6 << 1)) as u8,
-            (h6 >> 7) as u8,
-            (h6 >> 15) as u8,
-            ((h6 >> 23) | (h7 << 3)) as u8,
-            (h7 >> 5) as u8,
-            (h7 >> 13) as u8,
-            ((h7 >> 21) | (h8 << 4)) as u8,
-            (h8 >> 4) as u8,
-            (h8 >> 12) as u8,
-            ((h8 >> 20) | (h9 << 6)) as u8,
-            (h9 >> 2) as u8,
-            (h9 >> 10) as u8,
-            (h9 >> 18) as u8,
-        ]
+    /// Like `to_bytes`, but writes the encoding into `out`, e.g. straight
+    /// into a larger frame.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `out` is exactly 32 bytes long.
+    pub fn encode_into(&self, out: &mut [u8]) {
+        assert_eq!(out.len(), 32, "encode_into: out must be 32 bytes");
+        out.copy_from_slice(&self.to_bytes());
     }
 
-    pub fn maybe_swap_with(&mut self, other: &mut FieldElement, do_swap: i32) {
-        let &mut FieldElement(f) = self;
-        let &mut FieldElement(g) = other;
-        let [f0, f1, f2, f3, f4, f5, f6, f7, f8, f9] = f;
-        let [g0, g1, g2, g3, g4, g5, g6, g7, g8, g9] = g;
-        let mut x0 = f0 ^ g0;
-        let mut x1 = f1 ^ g1;
-        let mut x2 = f2 ^ g2;
-        let mut x3 = f3 ^ g3;
-        let mut x4 = f4 ^ g4;
-        let mut x5 = f5 ^ g5;
-        let mut x6 = f6 ^ g6;
-        let mut x7 = f7 ^ g7;
-        let mut x8 = f8 ^ g8;
-        let mut x9 = f9 ^ g9;
-        let b = -do_swap;
-        x0 &= b;
-        x1 &= b;
-        x2 &= b;
-        x3 &= b;
-        x4 &= b;
-        x5 &= b;
-        x6 &= b;
-        x7 &= b;
-        x8 &= b;
-        x9 &= b;
-        *self = FieldElement([
-            f0 ^ x0,
-            f1 ^ x1,
-            f2 ^ x2,
-            f3 ^ x3,
-            f4 ^ x4,
-            f5 ^ x5,
-            f6 ^ x6,
-            f7 ^ x7,
-            f8 ^ x8,
-            f9 ^ x9,
-        ]);
-        *other = FieldElement([
-            g0 ^ x0,
-            g1 ^ x1,
-            g2 ^ x2,
-            g3 ^ x3,
-            g4 ^ x4,
-            g5 ^ x5,
-            g6 ^ x6,
-            g7 ^ x7,
-            g8 ^ x8,
-            g9 ^ x9,
-        ]);
+    /// Whether `bytes` is the canonical encoding of a field element, i.e.
+    /// a little-endian integer below p with the top bit clear. `from_bytes`
+    /// accepts more than this and reduces.
+    ///
+    /// Runs in constant time.
+    pub fn is_canonical_bytes(bytes: &[u8; 32]) -> bool {
+        fixed_time_eq(&FieldElement::from_bytes(bytes).to_bytes(), bytes)
     }
 
-    pub fn maybe_set(&mut self, other: &FieldElement, do_swap: i32) {
-        let &mut FieldElement(f) = self;
-        let &FieldElement(g) = other;
-        let [f0, f1, f2, f3, f4, f5, f6, f7, f8, f9] = f;
-        let [g0, g1, g2, g3, g4, g5, g6, g7, g8, g9] = g;
-        let mut x0 = f0 ^ g0;
-        let mut x1 = f1 ^ g1;
-        let mut x2 = f2 ^ g2;
-        let mut x3 = f3 ^ g3;
-        let mut x4 = f4 ^ g4;
-        let mut x5 = f5 ^ g5;
-        let mut x6 = f6 ^ g6;
-        let mut x7 = f7 ^ g7;
-        let mut x8 = f8 ^ g8;
-        let mut x9 = f9 ^ g9;
-        let b = -do_swap;
-        x0 &= b;
-        x1 &= b;
-        x2 &= b;
-        x3 &= b;
-        x4 &= b;
-        x5 &= b;
-        x6 &= b;
-        x7 &= b;
-        x8 &= b;
-        x9 &= b;
-        *self = FieldElement([
-            f0 ^ x0,
-            f1 ^ x1,
-            f2 ^ x2,
-            f3 ^ x3,
-            f4 ^ x4,
-            f5 ^ x5,
-            f6 ^ x6,
-            f7 ^ x7,
-            f8 ^ x8,
-            f9 ^ x9,
-        ]);
+    /// Decodes a u-coordinate the way RFC 7748 prescribes for X25519: the
+    /// most significant bit of the last byte is cleared first.
+    ///
+    /// `from_bytes` ignores that bit as well; this spells it out.
+    pub fn from_bytes_masked(s: &[u8; 32]) -> FieldElement {
+        let mut masked = *s;
+        masked[31] &= 127;
+        FieldElement::from_bytes(&masked)
     }
 
-    // h = f * 121666
-    // Can overlap h with f.
-    //
-    // Preconditions:
-    //    |f| bounded by 1.1*2^26,1.1*2^25,1.1*2^26,1.1*2^25,etc.
-    //
-    // Postconditions:
-    //    |h| bounded by 1.1*2^25,1.1*2^24,1.1*2^25,1.1*2^24,etc.
-    fn mul_121666(&self) -> FieldElement {
-        let &FieldElement(f) = self;
-
-        let mut h0 = i64::from(f[0]) * 121_666;
-        let mut h1 = i64::from(f[1]) * 121_666;
-        let mut h2 = i64::from(f[2]) * 121_666;
-        let mut h3 = i64::from(f[3]) * 121_666;
-        let mut h4 = i64::from(f[4]) * 121_666;
-        let mut h5 = i64::from(f[5]) * 121_666;
-        let mut h6 = i64::from(f[6]) * 121_666;
-        let mut h7 = i64::from(f[7]) * 121_666;
-        let mut h8 = i64::from(f[8]) * 121_666;
-        let mut h9 = i64::from(f[9]) * 121_666;
-
-        let carry9 = (h9 + (1 << 24)) >> 25;
-        h0 += carry9 * 19;
-        h9 -= carry9 << 25;
-        let carry1 = (h1 + (1 << 24)) >> 25;
-        h2 += carry1;
-        h1 -= carry1 << 25;
-        let carry3 = (h3 + (1 << 24)) >> 25;
-        h4 += carry3;
-        h3 -= carry3 << 25;
-        let carry5 = (h5 + (1 << 24)) >> 25;
-        h6 += carry5;
-        h5 -= carry5 << 25;
-        let carry7 = (h7 + (1 << 24)) >> 25;
-        h8 += carry7;
-        h7 -= carry7 << 25;
-
-        let carry0 = (h0 + (1 << 25)) >> 26;
-        h1 += carry0;
-        h0 -= carry0 << 26;
-        let carry2 = (h2 + (1 << 25)) >> 26;
-        h3 += carry2;
-        h2 -= carry2 << 26;
-        let carry4 = (h4 + (1 << 25)) >> 26;
-        h5 += carry4;
-        h4 -= carry4 << 26;
-        let carry6 = (h6 + (1 << 25)) >> 26;
-        h7 += carry6;
-        h6 -= carry6 << 26;
-        let carry8 = (h8 + (1 << 25)) >> 26;
-        h9 += carry8;
-        h8 -= carry8 << 26;
-
-        FieldElement([
-            h0 as i32, h1 as i32, h2 as i32, h3 as i32, h4 as i32, h5 as i32,
-            h6 as i32, h7 as i32, h8 as i32, h9 as i32,
-        ])
-    }
-
-    // h = f * f
-    // Can overlap h with f.
-    //
-    // Preconditions:
-    //    |f| bounded by 1.1*2^26,1.1*2^25,1.1*2^26,1.1*2^25,etc.
+    // `[a[0] * b[0], a[1] * b[1], a[2] * b[2], a[3] * b[3]]`
     //
-    // Postconditions:
-    //    |h| bounded by 1.1*2^25,1.1*2^24,1.1*2^25,1.1*2^24,etc.
-    // See fe_mul.c for discussion of implementation strategy.
-    fn square(&self) -> FieldElement {
-        let &FieldElement(f) = self;
-
-        let [f0, f1, f2, f3, f4, f5, f6, f7, f8, f9] = f;
-
-        let f0_2 = 2 * f0;
-        let f1_2 = 2 * f1;
-        let f2_2 = 2 * f2;
-        let f3_2 = 2 * f3;
-        let f4_2 = 2 * f4;
-        let f5_2 = 2 * f5;
-        let f6_2 = 2 * f6;
-        let f7_2 = 2 * f7;
-        let f5_38 = 38 * f5; // 1.31*2^30
-        let f6_19 = 19 * f6; // 1.31*2^30
-        let f7_38 = 38 * f7; // 1.31*2^30
-        let f8_19 = 19 * f8; // 1.31*2^30
-        let f9_38 = 38 * f9; // 1.31*2^30
-        let f0f0 = i64::from(f0) * i64::from(f0);
-        let f0f1_2 = i64::from(f0_2) * i64::from(f1);
-        let f0f2_2 = i64::from(f0_2) * i64::from(f2);
-        let f0f3_2 = i64::from(f0_2) * i64::from(f3);
-        let f0f4_2 = i64::from(f0_2) * i64::from(f4);
-        let f0f5_2 = i64::from(f0_2) * i64::from(f5);
-        let f0f6_2 = i64::from(f0_2) * i64::from(f6);
-        let f0f7_2 = i64::from(f0_2) * i64::from(f7);
-        let f0f8_2 = i64::from(f0_2) * i64::from(f8);
-        let f0f9_2 = i64::from(f0_2) * i64::from(f9);
-        let f1f1_2 = i64::from(f1_2) * i64::from(f1);
-        let f1f2_2 = i64::from(f1_2) * i64::from(f2);
-        let f1f3_4 = i64::from(f1_2) * i64::from(f3_2);
-        let f1f4_2 = i64::from(f1_2) * i64::from(f4);
-        let f1f5_4 = i64::from(f1_2) * i64::from(f5_2);
-        let f1f6_2 = i64::from(f1_2) * i64::from(f6);
-        let f1f7_4 = i64::from(f1_2) * i64::from(f7_2);
-        let f1f8_2 = i64::from(f1_2) * i64::from(f8);
-        let f1f9_76 = i64::from(f1_2) * i64::from(f9_38);
-        let f2f2 = i64::from(f2) * i64::from(f2);
-        let f2f3_2 = i64::from(f2_2) * i64::from(f3);
-        let f2f4_2 = i64::from(f2_2) * i64::from(f4);
-        let f2f5_2 = i64::from(f2_2) * i64::from(f5);
-        let f2f6_2 = i64::from(f2_2) * i64::from(f6);
-        let f2f7_2 = i64::from(f2_2) * i64::from(f7);
-        let f2f8_38 = i64::from(f2_2) * i64::from(f8_19);
-        let f2f9_38 = i64::from(f2) * i64::from(f9_38);
-        let f3f3_2 = i64::from(f3_2) * i64::from(f3);
-        let f3f4_2 = i64::from(f3_2) * i64::from(f4);
-        let f3f5_4 = i64::from(f3_2) * i64::from(f5_2);
-        let f3f6_2 = i64::from(f3_2) * i64::from(f6);
-        let f3f7_76 = i64::from(f3_2) * i64::from(f7_38);
-        let f3f8_38 = i64::from(f3_2) * i64::from(f8_19);
-        let f3f9_76 = i64::from(f3_2) * i64::from(f9_38);
-        let f4f4 = i64::from(f4) * i64::from(f4);
-        let f4f5_2 = i64::from(f4_2) * i64::from(f5);
-        let f4f6_38 = i64::from(f4_2) * i64::from(f6_19);
-        let f4f7_38 = i64::from(f4) * i64::from(f7_38);
-        let f4f8_38 = i64::from(f4_2) * i64::from(f8_19);
-        let f4f9_38 = i64::from(f4) * i64::from(f9_38);
-        let f5f5_38 = i64::from(f5) * i64::from(f5_38);
-        let f5f6_38 = i64::from(f5_2) * i64::from(f6_19);
-        let f5f7_76 = i64::from(f5_2) * i64::from(f7_38);
-        let f5f8_38 = i64::from(f5_2) * i64::from(f8_19);
-        let f5f9_76 = i64::from(f5_2) * i64::from(f9_38);
-        let f6f6_19 = i64::from(f6) * i64::from(f6_19);
-        let f6f7_38 = i64::from(f6) * i64::from(f7_38);
-        let f6f8_38 = i64::from(f6_2) * i64::from(f8_19);
-        let f6f9_38 = i64::from(f6) * i64::from(f9_38);
-        let f7f7_38 = i64::from(f7) * i64::from(f7_38);
-        let f7f8_38 = i64::from(f7_2) * i64::from(f8_19);
-        let f7f9_76 = i64::from(f7_2) * i64::from(f9_38);
-        let f8f8_19 = i64::from(f8) * i64::from(f8_19);
-        let f8f9_38 = i64::from(f8) * i64::from(f9_38);
-        let f9f9_38 = i64::from(f9) * i64::from(f9_38);
-        let mut h0 = f0f0 + f1f9_76 + f2f8_38 + f3f7_76 + f4f6_38 + f5f5_38;
-        let mut h1 = f0f1_2 + f2f9_38 + f3f8_38 + f4f7_38 + f5f6_38;
-        let mut h2 = f0f2_2 + f1f1_2 + f3f9_76 + f4f8_38 + f5f7_76 + f6f6_19;
-        let mut h3 = f0f3_2 + f1f2_2 + f4f9_38 + f5f8_38 + f6f7_38;
-        let mut h4 = f0f4_2 + f1f3_4 + f2f2 + f5f9_76 + f6f8_38 + f7f7_38;
-        let mut h5 = f0f5_2 + f1f4_2 + f2f3_2 + f6f9_38 + f7f8_38;
-        let mut h6 = f0f6_2 + f1f5_4 + f2f4_2 + f3f3_2 + f7f9_76 + f8f8_19;
-        let mut h7 = f0f7_2 + f1f6_2 + f2f5_2 + f3f4_2 + f8f9_38;
-        let mut h8 = f0f8_2 + f1f7_4 + f2f6_2 + f3f5_4 + f4f4 + f9f9_38;
-        let mut h9 = f0f9_2 + f1f8_2 + f2f7_2 + f3f6_2 + f4f5_2;
-
-        let carry0 = (h0 + (1 << 25)) >> 26;
-        h1 += carry0;
-        h0 -= carry0 << 26;
-        let carry4 = (h4 + (1 << 25)) >> 26;
-        h5 += carry4;
-        h4 -= carry4 << 26;
-
-        let carry1 = (h1 + (1 << 24)) >> 25;
-        h2 += carry1;
-        h1 -= carry1 << 25;
-        let carry5 = (h5 + (1 << 24)) >> 25;
-        h6 += carry5;
-        h5 -= carry5 << 25;
-
-        let carry2 = (h2 + (1 << 25)) >> 26;
-        h3 += carry2;
-        h2 -= carry2 << 26;
-        let carry6 = (h6 + (1 << 25)) >> 26;
-        h7 += carry6;
-        h6 -= carry6 << 26;
-
-        let carry3 = (h3 + (1 << 24)) >> 25;
-        h4 += carry3;
-        h3 -= carry3 << 25;
-        let carry7 = (h7 + (1 << 24)) >> 25;
-        h8 += carry7;
-        h7 -= carry7 << 25;
-
-        let carry4 = (h4 + (1 << 25)) >> 26;
-        h5 += carry4;
-        h4 -= carry4 << 26;
-        let carry8 = (h8 + (1 << 25)) >> 26;
-        h9 += carry8;
-        h8 -= carry8 << 26;
-
-        let carry9 = (h9 + (1 << 24)) >> 25;
-        h0 += carry9 * 19;
-        h9 -= carry9 << 25;
-
-        let carrya = (h0 + (1 << 25)) >> 26;
-        h1 += carrya;
-        h0 -= carrya << 26;
-
-        FieldElement([
-            h0 as i32, h1 as i32, h2 as i32, h3 as i32, h4 as i32, h5 as i32,
-            h6 as i32, h7 as i32, h8 as i32, h9 as i32,
-        ])
-    }
-
-    fn square_and_double(&self) -> FieldElement {
-        let &FieldElement(f) = self;
-
-        let [f0, f1, f2, f3, f4, f5, f6, f7, f8, f9] = f;
-
-        let f0_2 = 2 * f0;
-        let f1_2 = 2 * f1;
-        let f2_2 = 2 * f2;
-        let f3_2 = 2 * f3;
-        let f4_2 = 2 * f4;
-        let f5_2 = 2 * f5;
-        let f6_2 = 2 * f6;
-        let f7_2 = 2 * f7;
-        let f5_38 = 38 * f5; // 1.959375*2^30
-        let f6_19 = 19 * f6; // 1.959375*2^30
-        let f7_38 = 38 * f7; // 1.959375*2^30
-        let f8_19 = 19 * f8; // 1.959375*2^30
-        let f9_38 = 38 * f9; // 1.959375*2^30
-        let f0f0 = i64::from(f0) * i64::from(f0);
-        let f0f1_2 = i64::from(f0_2) * i64::from(f1);
-        let f0f2_2 = i64::from(f0_2) * i64::from(f2);
-        let f0f3_2 = i64::from(f0_2) * i64::from(f3);
-        let f0f4_2 = i64::from(f0_2) * i64::from(f4);
-        let f0f5_2 = i64::from(f0_2) * i64::from(f5);
-        let f0f6_2 = i64::from(f0_2) * i64::from(f6);
-        let f0f7_2 = i64::from(f0_2) * i64::from(f7);
-        let f0f8_2 = i64::from(f0_2) * i64::from(f8);
-        let f0f9_2 = i64::from(f0_2) * i64::from(f9);
-        let f1f1_2 = i64::from(f1_2) * i64::from(f1);
-        let f1f2_2 = i64::from(f1_2) * i64::from(f2);
-        let f1f3_4 = i64::from(f1_2) * i64::from(f3_2);
-        let f1f4_2 = i64::from(f1_2) * i64::from(f4);
-        let f1f5_4 = i64::from(f1_2) * i64::from(f5_2);
-        let f1f6_2 = i64::from(f1_2) * i64::from(f6);
-        let f1f7_4 = i64::from(f1_2) * i64::from(f7_2);
-        let f1f8_2 = i64::from(f1_2) * i64::from(f8);
-        let f1f9_76 = i64::from(f1_2) * i64::from(f9_38);
-        let f2f2 = i64::from(f2) * i64::from(f2);
-        let f2f3_2 = i64::from(f2_2) * i64::from(f3);
-        let f2f4_2 = i64::from(f2_2) * i64::from(f4);
-        let f2f5_2 = i64::from(f2_2) * i64::from(f5);
-        let f2f6_2 = i64::from(f2_2) * i64::from(f6);
-        let f2f7_2 = i64::from(f2_2) * i64::from(f7);
-        let f2f8_38 = i64::from(f2_2) * i64::from(f8_19);
-        let f2f9_38 = i64::from(f2) * i64::from(f9_38);
-        let f3f3_2 = i64::from(f3_2) * i64::from(f3);
-        let f3f4_2 = i64::from(f3_2) * i64::from(f4);
-        let f3f5_4 = i64::from(f3_2) * i64::from(f5_2);
-        let f3f6_2 = i64::from(f3_2) * i64::from(f6);
-        let f3f7_76 = i64::from(f3_2) * i64::from(f7_38);
-        let f3f8_38 = i64::from(f3_2) * i64::from(f8_19);
-        let f3f9_76 = i64::from(f3_2) * i64::from(f9_38);
-        let f4f4 = i64::from(f4) * i64::from(f4);
-        let f4f5_2 = i64::from(f4_2) * i64::from(f5);
-        let f4f6_38 = i64::from(f4_2) * i64::from(f6_19);
-        let f4f7_38 = i64::from(f4) * i64::from(f7_38);
-        let f4f8_38 = i64::from(f4_2) * i64::from(f8_19);
-        let f4f9_38 = i64::from(f4) * i64::from(f9_38);
-        let f5f5_38 = i64::from(f5) * i64::from(f5_38);
-        let f5f6_38 = i64::from(f5_2) * i64::from(f6_19);
-        let f5f7_76 = i64::from(f5_2) * i64::from(f7_38);
-        let f5f8_38 = i64::from(f5_2) * i64::from(f8_19);
-        let f5f9_76 = i64::from(f5_2) * i64::from(f9_38);
-        let f6f6_19 = i64::from(f6) * i64::from(f6_19);
-        let f6f7_38 = i64::from(f6) * i64::from(f7_38);
-        let f6f8_38 = i64::from(f6_2) * i64::from(f8_19);
-        let f6f9_38 = i64::from(f6) * i64::from(f9_38);
-        let f7f7_38 = i64::from(f7) * i64::from(f7_38);
-        let f7f8_38 = i64::from(f7_2) * i64::from(f8_19);
-        let f7f9_76 = i64::from(f7_2) * i64::from(f9_38);
-        let f8f8_19 = i64::from(f8) * i64::from(f8_19);
-        let f8f9_38 = i64::from(f8) * i64::from(f9_38);
-        let f9f9_38 = i64::from(f9) * i64::from(f9_38);
-        let mut h0 = f0f0 + f1f9_76 + f2f8_38 + f3f7_76 + f4f6_38 + f5f5_38;
-        let mut h1 = f0f1_2 + f2f9_38 + f3f8_38 + f4f7_38 + f5f6_38;
-        let mut h2 = f0f2_2 + f1f1_2 + f3f9_76 + f4f8_38 + f5f7_76 + f6f6_19;
-        let mut h3 = f0f3_2 + f1f2_2 + f4f9_38 + f5f8_38 + f6f7_38;
-        let mut h4 = f0f4_2 + f1f3_4 + f2f2 + f5f9_76 + f6f8_38 + f7f7_38;
-        let mut h5 = f0f5_2 + f1f4_2 + f2f3_2 + f6f9_38 + f7f8_38;
-        let mut h6 = f0f6_2 + f1f5_4 + f2f4_2 + f3f3_2 + f7f9_76 + f8f8_19;
-        let mut h7 = f0f7_2 + f1f6_2 + f2f5_2 + f3f4_2 + f8f9_38;
-        let mut h8 = f0f8_2 + f1f7_4 + f2f6_2 + f3f5_4 + f4f4 + f9f9_38;
-        let mut h9 = f0f9_2 + f1f8_2 + f2f7_2 + f3f6_2 + f4f5_2;
-        let mut carry0: i64;
-        let carry1: i64;
-        let carry2: i64;
-        let carry3: i64;
-        let mut carry4: i64;
-        let carry5: i64;
-        let carry6: i64;
-        let carry7: i64;
-        let carry8: i64;
-        let carry9: i64;
-
-        h0 += h0;
-        h1 += h1;
-        h2 += h2;
-        h3 += h3;
-        h4 += h4;
-        h5 += h5;
-        h6 += h6;
-        h7 += h7;
-        h8 += h8;
-        h9 += h9;
-
-        carry0 = (h0 + (1 << 25)) >> 26;
-        h1 += carry0;
-        h0 -= carry0 << 26;
-        carry4 = (h4 + (1 << 25)) >> 26;
-        h5 += carry4;
-        h4 -= carry4 << 26;
-
-        carry1 = (h1 + (1 << 24)) >> 25;
-        h2 += carry1;
-        h1 -= carry1 << 25;
-        carry5 = (h5 + (1 << 24)) >> 25;
-        h6 += carry5;
-        h5 -= carry5 << 25;
-
-        carry2 = (h2 + (1 << 25)) >> 26;
-        h3 += carry2;
-        h2 -= carry2 << 26;
-        carry6 = (h6 + (1 << 25)) >> 26;
-        h7 += carry6;
-        h6 -= carry6 << 26;
-
-        carry3 = (h3 + (1 << 24)) >> 25;
-        h4 += carry3;
-        h3 -= carry3 << 25;
-        carry7 = (h7 + (1 << 24)) >> 25;
-        h8 += carry7;
-        h7 -= carry7 << 25;
-
-        carry4 = (h4 + (1 << 25)) >> 26;
-        h5 += carry4;
-        h4 -= carry4 << 26;
-        carry8 = (h8 + (1 << 25)) >> 26;
-        h9 += carry8;
-        h8 -= carry8 << 26;
-
-        carry9 = (h9 + (1 << 24)) >> 25;
-        h0 += carry9 * 19;
-        h9 -= carry9 << 25;
-
-        carry0 = (h0 + (1 << 25)) >> 26;
-        h1 += carry0;
-        h0 -= carry0 << 26;
-
-        FieldElement([
-            h0 as i32, h1 as i32, h2 as i32, h3 as i32, h4 as i32, h5 as i32,
-            h6 as i32, h7 as i32, h8 as i32, h9 as i32,
-        ])
+    // The point formulas have four independent products in a row; with the
+    // `simd` feature on an AVX2 capable CPU they are computed side by side.
+    #[inline]
+    pub(crate) fn mul4(
+        a: [FieldElement; 4],
+        b: [FieldElement; 4],
+    ) -> [FieldElement; 4] {
+        #[cfg(all(
+            feature = "simd",
+            target_arch = "x86_64",
+            not(feature = "u64_backend"),
+            not(feature = "portable")
+        ))]
+        {
+            if std::is_x86_feature_detected!("avx2") {
+                return unsafe { crate::field_avx2::mul4(&a, &b) };
+            }
+        }
+
+        [a[0] * b[0], a[1] * b[1], a[2] * b[2], a[3] * b[3]]
     }
 
+    /// Squares `self` `k` times, i.e. computes `self^(2^k)`, for the long
+    /// runs of squarings in addition chains. `pow2k(0)` is `self`.
+    #[must_use]
+    pub fn pow2k(&self, k: u32) -> FieldElement {
+        let mut x = *self;
+        for _ in 0..k {
+            x = x.square();
+        }
+        x
+    }
+
+    #[must_use]
     pub fn invert(&self) -> FieldElement {
         let z1 = *self;
 
@@ -1097,37 +271,37 @@ impl FieldElement {
         let z_5_0 = z9 * z22;
 
         // qhasm: z_10_5 = z_5_0^2^5
-        let z_10_5 = (0..5).fold(z_5_0, |z_5_n, _| z_5_n.square());
+        let z_10_5 = z_5_0.pow2k(5);
 
         // qhasm: z_10_0 = z_10_5*z_5_0
         let z_10_0 = z_10_5 * z_5_0;
 
         // qhasm: z_20_10 = z_10_0^2^10
-        let z_20_10 = (0..10).fold(z_10_0, |x, _| x.square());
+        let z_20_10 = z_10_0.pow2k(10);
 
         // qhasm: z_20_0 = z_20_10*z_10_0
         let z_20_0 = z_20_10 * z_10_0;
 
         // qhasm: z_40_20 = z_20_0^2^20
-        let z_40_20 = (0..20).fold(z_20_0, |x, _| x.square());
+        let z_40_20 = z_20_0.pow2k(20);
 
         // qhasm: z_40_0 = z_40_20*z_20_0
         let z_40_0 = z_40_20 * z_20_0;
 
         // qhasm: z_50_10 = z_40_0^2^10
-        let z_50_10 = (0..10).fold(z_40_0, |x, _| x.square());
+        let z_50_10 = z_40_0.pow2k(10);
 
         // qhasm: z_50_0 = z_50_10*z_10_0
         let z_50_0 = z_50_10 * z_10_0;
 
         // qhasm: z_100_50 = z_50_0^2^50
-        let z_100_50 = (0..50).fold(z_50_0, |x, _| x.square());
+        let z_100_50 = z_50_0.pow2k(50);
 
         // qhasm: z_100_0 = z_100_50*z_50_0
         let z_100_0 = z_100_50 * z_50_0;
 
         // qhasm: z_200_100 = z_100_0^2^100
-        let z_200_100 = (0..100).fold(z_100_0, |x, _| x.square());
+        let z_200_100 = z_100_0.pow2k(100);
 
         // qhasm: z_200_0 = z_200_100*z_100_0
         // asm 1: fe_mul(>z_200_0=fe#3,<z_200_100=fe#4,<z_100_0=fe#3);
@@ -1135,13 +309,13 @@ impl FieldElement {
         let z_200_0 = z_200_100 * z_100_0;
 
         // qhasm: z_250_50 = z_200_0^2^50
-        let z_250_50 = (0..50).fold(z_200_0, |x, _| x.square());
+        let z_250_50 = z_200_0.pow2k(50);
 
         // qhasm: z_250_0 = z_250_50*z_50_0
         let z_250_0 = z_250_50 * z_50_0;
 
         // qhasm: z_255_5 = z_250_0^2^5
-        let z_255_5 = (0..5).fold(z_250_0, |x, _| x.square());
+        let z_255_5 = z_250_0.pow2k(5);
 
         // qhasm: z_255_21 = z_255_5*z11
         // asm 1: fe_mul(>z_255_21=fe#12,<z_255_5=fe#2,<z11=fe#1);
@@ -1149,1407 +323,309 @@ impl FieldElement {
         z_255_5 * z11
     }
 
-    fn is_nonzero(&self) -> bool {
-        let bs = self.to_bytes();
-        let zero = [0; 32];
-        !fixed_time_eq(bs.as_ref(), zero.as_ref())
+    /// Replaces every element by its inverse, with Montgomery's trick: one
+    /// `invert` and three multiplications per element instead of an
+    /// `invert` each.
+    ///
+    /// Zeros are left unchanged, like `invert` maps zero to zero, and don't
+    /// spoil the rest of the batch. Runs in constant time.
+    pub fn batch_invert(elements: &mut [FieldElement]) {
+        for chunk in elements.chunks_mut(BATCH_INVERT_CHUNK) {
+            // products[i] = a[0] * ... * a[i - 1], counting zeros as one.
+            let mut products = [FE_ONE; BATCH_INVERT_CHUNK];
+            let mut is_zero = [0i32; BATCH_INVERT_CHUNK];
+            let mut acc = FE_ONE;
+            for (i, a) in chunk.iter_mut().enumerate() {
+                products[i] = acc;
+                is_zero[i] = i32::from(!a.is_nonzero());
+                a.maybe_set(&FE_ONE, is_zero[i]);
+                acc = acc * *a;
+            }
+
+            // inv = 1 / (a[0] * ... * a[i]) at the top of each iteration.
+            let mut inv = acc.invert();
+            for (i, a) in chunk.iter_mut().enumerate().rev() {
+                let mut a_inv = inv * products[i];
+                inv = inv * *a;
+                a_inv.maybe_set(&FE_ZERO, is_zero[i]);
+                *a = a_inv;
+            }
+        }
+    }
+
+    /// Reduces a 64-byte little-endian integer modulo p, e.g. 64 bytes of
+    /// hash output in hash-to-field. Unlike `from_bytes` every bit counts.
+    #[must_use]
+    pub fn from_bytes_wide_reduce(bytes: &[u8; 64]) -> FieldElement {
+        let mut lo = [0u8; 32];
+        let mut hi = [0u8; 32];
+        lo.copy_from_slice(&bytes[..32]);
+        hi.copy_from_slice(&bytes[32..]);
+
+        // x = lo + 2^256 hi = lo + 38 hi (mod p). from_bytes drops bit 255
+        // of both halves, worth 2^255 = 19 in lo and 38 * 19 in hi.
+        let top = u64::from(lo[31] >> 7) * 19 + u64::from(hi[31] >> 7) * 722;
+        FieldElement::from_bytes(&lo)
+            + (FieldElement::from_bytes(&hi).mul_small(38)
+                + FieldElement::from_u64(top))
     }
 
+    fn is_nonzero(&self) -> bool { !ct_is_zero(&self.to_bytes()) }
+
     fn is_negative(&self) -> bool { (self.to_bytes()[0] & 1) != 0 }
 
-    fn neg(&self) -> FieldElement {
-        let &FieldElement(f) = self;
-        FieldElement([
-            -f[0], -f[1], -f[2], -f[3], -f[4], -f[5], -f[6], -f[7], -f[8],
-            -f[9],
-        ])
+    /// Negates `self` if `cond` is 1 and leaves it alone if it is 0,
+    /// without branching on `cond`.
+    ///
+    /// `cond` must be 0 or 1, like the flag of `maybe_set`.
+    pub fn conditional_negate(&mut self, cond: i32) {
+        let minus_self = self.neg();
+        self.maybe_set(&minus_self, cond);
+    }
+
+    /// Sets `self` to `other` if `choice` is 1 and leaves it alone if it is
+    /// 0, without branching on `choice`.
+    ///
+    /// `choice` must be 0 or 1; debug builds panic on anything else. This
+    /// is `maybe_set` under the name other constant-time code uses.
+    pub fn conditional_assign(&mut self, other: &FieldElement, choice: u8) {
+        self.maybe_set(other, i32::from(choice));
+    }
+
+    /// Swaps `self` and `other` if `choice` is 1 and leaves both alone if it
+    /// is 0, without branching on `choice`.
+    ///
+    /// `choice` must be 0 or 1, as for `conditional_assign`. This is
+    /// `maybe_swap_with` under the name other constant-time code uses.
+    pub fn conditional_swap(&mut self, other: &mut FieldElement, choice: u8) {
+        self.maybe_swap_with(other, i32::from(choice));
+    }
+
+    // |f|, the non-negative one of f and -f.
+    fn abs(&self) -> FieldElement {
+        let mut r = *self;
+        r.conditional_negate(i32::from(self.is_negative()));
+        r
+    }
+
+    // Returns (true, sqrt(u/v)) if u/v is a square and (false, sqrt(i*u/v))
+    // otherwise, where i = sqrt(-1). The root is always non-negative.
+    // u = 0 gives (true, 0) and v = 0 gives (false, 0) for nonzero u.
+    fn sqrt_ratio_m1(
+        u: &FieldElement,
+        v: &FieldElement,
+    ) -> (bool, FieldElement) {
+        let v3 = v.square() * *v;
+        let v7 = v3.square() * *v;
+        let mut r = (*u * v3) * (*u * v7).pow25523();
+        let check = *v * r.square();
+
+        let u_neg = u.neg();
+        let correct_sign_sqrt = check == *u;
+        let flipped_sign_sqrt = check == u_neg;
+        let flipped_sign_sqrt_i = check == u_neg * FE_SQRTM1;
+
+        let r_prime = r * FE_SQRTM1;
+        r.maybe_set(
+            &r_prime,
+            i32::from(flipped_sign_sqrt | flipped_sign_sqrt_i),
+        );
+
+        (correct_sign_sqrt | flipped_sign_sqrt, r.abs())
     }
 
+    #[must_use]
     fn pow25523(&self) -> FieldElement {
         let z2 = self.square();
-        let z8 = (0..2).fold(z2, |x, _| x.square());
+        let z8 = z2.pow2k(2);
         let z9 = *self * z8;
         let z11 = z2 * z9;
         let z22 = z11.square();
         let z_5_0 = z9 * z22;
-        let z_10_5 = (0..5).fold(z_5_0, |x, _| x.square());
+        let z_10_5 = z_5_0.pow2k(5);
         let z_10_0 = z_10_5 * z_5_0;
-        let z_20_10 = (0..10).fold(z_10_0, |x, _| x.square());
+        let z_20_10 = z_10_0.pow2k(10);
         let z_20_0 = z_20_10 * z_10_0;
-        let z_40_20 = (0..20).fold(z_20_0, |x, _| x.square());
+        let z_40_20 = z_20_0.pow2k(20);
         let z_40_0 = z_40_20 * z_20_0;
-        let z_50_10 = (0..10).fold(z_40_0, |x, _| x.square());
+        let z_50_10 = z_40_0.pow2k(10);
         let z_50_0 = z_50_10 * z_10_0;
-        let z_100_50 = (0..50).fold(z_50_0, |x, _| x.square());
+        let z_100_50 = z_50_0.pow2k(50);
         let z_100_0 = z_100_50 * z_50_0;
-        let z_200_100 = (0..100).fold(z_100_0, |x, _| x.square());
+        let z_200_100 = z_100_0.pow2k(100);
         let z_200_0 = z_200_100 * z_100_0;
-        let z_250_50 = (0..50).fold(z_200_0, |x, _| x.square());
+        let z_250_50 = z_200_0.pow2k(50);
         let z_250_0 = z_250_50 * z_50_0;
-        let z_252_2 = (0..2).fold(z_250_0, |x, _| x.square());
+        let z_252_2 = z_250_0.pow2k(2);
         z_252_2 * *self
     }
 }
 
-#[doc(hidden)]
-#[derive(Clone, Copy)]
-pub struct GeP2 {
-    x: FieldElement,
-    y: FieldElement,
-    z: FieldElement,
-}
-
-#[doc(hidden)]
-#[derive(Clone, Copy)]
-pub struct GeP3 {
-    x: FieldElement,
-    y: FieldElement,
-    z: FieldElement,
-    t: FieldElement,
-}
-
-#[doc(hidden)]
-#[derive(Clone, Copy)]
-pub struct GeP1P1 {
-    x: FieldElement,
-    y: FieldElement,
-    z: FieldElement,
-    t: FieldElement,
-}
-
-#[doc(hidden)]
-#[derive(Clone, Copy)]
-pub struct GePrecomp {
-    y_plus_x: FieldElement,
-    y_minus_x: FieldElement,
-    xy2d: FieldElement,
-}
-
-#[doc(hidden)]
-#[derive(Clone, Copy)]
-pub struct GeCached {
-    y_plus_x: FieldElement,
-    y_minus_x: FieldElement,
-    z: FieldElement,
-    t2d: FieldElement,
-}
-
-impl GeP1P1 {
-    fn to_p2(&self) -> GeP2 {
-        GeP2 {
-            x: self.x * self.t,
-            y: self.y * self.z,
-            z: self.z * self.t,
-        }
-    }
-
-    fn to_p3(&self) -> GeP3 {
-        GeP3 {
-            x: self.x * self.t,
-            y: self.y * self.z,
-            z: self.z * self.t,
-            t: self.x * self.y,
-        }
-    }
+/// Computes `scalar * B` and returns its 32-byte encoding, with `B` the
+/// Ed25519 base point.
+///
+/// This is the public-key half of Ed25519 key generation, or a Schnorr
+/// commitment, without going through the internal point types. Runs in
+/// constant time.
+///
+/// The scalar is little-endian and must satisfy `scalar[31] <= 127`, which
+/// every clamped scalar and every scalar reduced modulo the group order
+/// does.
+pub fn ed25519_scalarmult_base_compressed(scalar: &[u8; 32]) -> [u8; 32] {
+    ge_scalarmult_base(scalar).to_bytes()
 }
 
-impl GeP2 {
-    fn zero() -> GeP2 {
-        GeP2 {
-            x: FE_ZERO,
-            y: FE_ONE,
-            z: FE_ONE,
-        }
-    }
-
-    pub fn to_bytes(&self) -> [u8; 32] {
-        let recip = self.z.invert();
-        let x = self.x * recip;
-        let y = self.y * recip;
-        let mut bs = y.to_bytes();
-        bs[31] ^= (if x.is_negative() { 1 } else { 0 }) << 7;
-        bs
-    }
-
-    fn dbl(&self) -> GeP1P1 {
-        let xx = self.x.square();
-        let yy = self.y.square();
-        let b = self.z.square_and_double();
-        let a = self.x + self.y;
-        let aa = a.square();
-        let y3 = yy + xx;
-        let z3 = yy - xx;
-        let x3 = aa - y3;
-        let t3 = b - z3;
-
-        GeP1P1 {
-            x: x3,
-            y: y3,
-            z: z3,
-            t: t3,
-        }
-    }
-
-    fn slide(a: &[u8]) -> [i8; 256] {
-        let mut r = [0i8; 256];
-        for i in 0..256 {
-            r[i] = (1 & (a[i >> 3] >> (i & 7))) as i8;
-        }
-        for i in 0..256 {
-            if r[i] != 0 {
-                for b in 1..min(7, 256 - i) {
-                    if r[i + b] != 0 {
-                        if r[i] + (r[i + b] << b) <= 15 {
-                            r[i] += r[i + b] << b;
-                            r[i + b] = 0;
-                        } else if r[i] - (r[i + b] << b) >= -15 {
-                            r[i] -= r[i + b] << b;
-                            for k in r.iter_mut().skip(i + b) {
-                                if *k == 0 {
-                                    *k = 1;
-                                    break;
-                                }
-                                *k = 0;
-                            }
-                        } else {
-                            break;
-                        }
-                    }
-                }
-            }
-        }
-
-        r
-    }
+// batch_invert inverts this many elements at a time, so the running
+// products stay on the stack.
+const BATCH_INVERT_CHUNK: usize = 64;
 
-    // r = a * A + b * B
-    // where a = a[0]+256*a[1]+...+256^31 a[31].
-    // and b = b[0]+256*b[1]+...+256^31 b[31].
-    // B is the Ed25519 base point (x,4/5) with x positive.
-    pub fn double_scalarmult_vartime(
-        a_scalar: &[u8],
-        a_point: GeP3,
-        b_scalar: &[u8],
-    ) -> GeP2 {
-        let aslide = GeP2::slide(a_scalar);
-        let bslide = GeP2::slide(b_scalar);
+// Points are processed this many at a time, so the tables stay on the
+// stack.
+const MULTISCALAR_CHUNK: usize = 8;
 
-        let mut ai = [GeCached {
+/// Computes `scalars[0] * points[0] + scalars[1] * points[1] + ...`.
+///
+/// Uses Straus's method: every point gets a table of its odd multiples
+/// and the doublings are shared between all of them.
+///
+/// This is **not** constant time, the running time depends on the
+/// scalars. Only use it with public scalars.
+///
+/// Each scalar is little-endian and must be below 2^255, i.e.
+/// `scalar[31] <= 127`.
+///
+/// # Panics
+///
+/// Panics if `scalars` and `points` have different lengths.
+pub fn multiscalar_mul(scalars: &[[u8; 32]], points: &[GeP3]) -> GeP3 {
+    assert_eq!(scalars.len(), points.len());
+
+    let mut sum = GeP3::zero();
+    for (scalars, points) in scalars
+        .chunks(MULTISCALAR_CHUNK)
+        .zip(points.chunks(MULTISCALAR_CHUNK))
+    {
+        let mut slides = [[0i8; 256]; MULTISCALAR_CHUNK];
+        let mut tables = [[GeCached {
             y_plus_x: FE_ZERO,
             y_minus_x: FE_ZERO,
             z: FE_ZERO,
             t2d: FE_ZERO,
-        }; 8]; // A,3A,5A,7A,9A,11A,13A,15A
-        ai[0] = a_point.to_cached();
-        let a2 = a_point.dbl().to_p3();
-        ai[1] = (a2 + ai[0]).to_p3().to_cached();
-        ai[2] = (a2 + ai[1]).to_p3().to_cached();
-        ai[3] = (a2 + ai[2]).to_p3().to_cached();
-        ai[4] = (a2 + ai[3]).to_p3().to_cached();
-        ai[5] = (a2 + ai[4]).to_p3().to_cached();
-        ai[6] = (a2 + ai[5]).to_p3().to_cached();
-        ai[7] = (a2 + ai[6]).to_p3().to_cached();
-
-        let mut r = GeP2::zero();
-
-        let mut i: usize = 255;
-        loop {
-            if aslide[i] != 0 || bslide[i] != 0 {
-                break;
-            }
-            if i == 0 {
-                return r;
-            }
-            i -= 1;
+        }; 8]; MULTISCALAR_CHUNK];
+        for (j, (scalar, point)) in scalars.iter().zip(points).enumerate() {
+            slides[j] = GeP2::slide(scalar);
+            tables[j] = point.odd_multiples();
         }
+        let n = scalars.len();
 
-        loop {
+        let mut r = GeP3::zero();
+        for i in (0..256).rev() {
             let mut t = r.dbl();
-            if aslide[i] > 0 {
-                t = t.to_p3() + ai[(aslide[i] / 2) as usize];
-            } else if aslide[i] < 0 {
-                t = t.to_p3() - ai[(-aslide[i] / 2) as usize];
-            }
-
-            if bslide[i] > 0 {
-                t = t.to_p3() + BI[(bslide[i] / 2) as usize];
-            } else if bslide[i] < 0 {
-                t = t.to_p3() - BI[(-bslide[i] / 2) as usize];
-            }
-
-            r = t.to_p2();
-
-            if i == 0 {
-                return r;
-            }
-            i -= 1;
-        }
-    }
-}
-
-impl GeP3 {
-    pub fn from_bytes_negate_vartime(s: &[u8]) -> Option<GeP3> {
-        let y = FieldElement::from_bytes(s);
-        let z = FE_ONE;
-        let y_squared = y.square();
-        let u = y_squared - FE_ONE;
-        let v = (y_squared * FE_D) + FE_ONE;
-        let v_raise_3 = v.square() * v;
-        let v_raise_7 = v_raise_3.square() * v;
-        let uv7 = v_raise_7 * u; // Is this commutative? u comes second in the code, but not in the
-                                 // notation...
-
-        let mut x = uv7.pow25523() * v_raise_3 * u;
-
-        let vxx = x.square() * v;
-        let check = vxx - u;
-        if check.is_nonzero() {
-            let check2 = vxx + u;
-            if check2.is_nonzero() {
-                return None;
+            for (slide, table) in slides[..n].iter().zip(&tables[..n]) {
+                if slide[i] > 0 {
+                    t = t.to_p3() + table[(slide[i] / 2) as usize];
+                } else if slide[i] < 0 {
+                    t = t.to_p3() - table[(-slide[i] / 2) as usize];
+                }
             }
-            x = x * FE_SQRTM1;
-        }
-
-        if x.is_negative() == ((s[31] >> 7) != 0) {
-            x = x.neg();
-        }
-
-        let t = x * y;
-
-        Some(GeP3 { x, y, z, t })
-    }
-
-    fn to_p2(&self) -> GeP2 {
-        GeP2 {
-            x: self.x,
-            y: self.y,
-            z: self.z,
-        }
-    }
-
-    fn to_cached(&self) -> GeCached {
-        GeCached {
-            y_plus_x: self.y + self.x,
-            y_minus_x: self.y - self.x,
-            z: self.z,
-            t2d: self.t * FE_D2,
-        }
-    }
-
-    fn zero() -> GeP3 {
-        GeP3 {
-            x: FE_ZERO,
-            y: FE_ONE,
-            z: FE_ONE,
-            t: FE_ZERO,
+            r = t.to_p3();
         }
-    }
-
-    fn dbl(&self) -> GeP1P1 { self.to_p2().dbl() }
-
-    pub fn to_bytes(&self) -> [u8; 32] {
-        let recip = self.z.invert();
-        let x = self.x * recip;
-        let y = self.y * recip;
-        let mut bs = y.to_bytes();
-        bs[31] ^= (if x.is_negative() { 1 } else { 0 }) << 7;
-        bs
-    }
-}
 
-impl Add<GeCached> for GeP3 {
-    type Output = GeP1P1;
-
-    fn add(self, _rhs: GeCached) -> GeP1P1 {
-        let y1_plus_x1 = self.y + self.x;
-        let y1_minus_x1 = self.y - self.x;
-        let a = y1_plus_x1 * _rhs.y_plus_x;
-        let b = y1_minus_x1 * _rhs.y_minus_x;
-        let c = _rhs.t2d * self.t;
-        let zz = self.z * _rhs.z;
-        let d = zz + zz;
-        let x3 = a - b;
-        let y3 = a + b;
-        let z3 = d + c;
-        let t3 = d - c;
-
-        GeP1P1 {
-            x: x3,
-            y: y3,
-            z: z3,
-            t: t3,
-        }
+        sum = (sum + r.to_cached()).to_p3();
     }
-}
 
-impl Add<GePrecomp> for GeP3 {
-    type Output = GeP1P1;
-
-    fn add(self, _rhs: GePrecomp) -> GeP1P1 {
-        let y1_plus_x1 = self.y + self.x;
-        let y1_minus_x1 = self.y - self.x;
-        let a = y1_plus_x1 * _rhs.y_plus_x;
-        let b = y1_minus_x1 * _rhs.y_minus_x;
-        let c = _rhs.xy2d * self.t;
-        let d = self.z + self.z;
-        let x3 = a - b;
-        let y3 = a + b;
-        let z3 = d + c;
-        let t3 = d - c;
-
-        GeP1P1 {
-            x: x3,
-            y: y3,
-            z: z3,
-            t: t3,
-        }
-    }
+    sum
 }
 
-impl Sub<GeCached> for GeP3 {
-    type Output = GeP1P1;
-
-    fn sub(self, _rhs: GeCached) -> GeP1P1 {
-        let y1_plus_x1 = self.y + self.x;
-        let y1_minus_x1 = self.y - self.x;
-        let a = y1_plus_x1 * _rhs.y_minus_x;
-        let b = y1_minus_x1 * _rhs.y_plus_x;
-        let c = _rhs.t2d * self.t;
-        let zz = self.z * _rhs.z;
-        let d = zz + zz;
-        let x3 = a - b;
-        let y3 = a + b;
-        let z3 = d - c;
-        let t3 = d + c;
-
-        GeP1P1 {
-            x: x3,
-            y: y3,
-            z: z3,
-            t: t3,
+/// Writes the 32-byte encoding of `points[i]` to `out[i]`, the same bytes
+/// as `points[i].to_bytes()`, with a single field inversion per 64 points
+/// instead of one per point.
+///
+/// Uses [`FieldElement::batch_invert`] on the Z coordinates, so it needs no
+/// allocation. Runs in constant time.
+///
+/// # Panics
+///
+/// Panics if `points` and `out` have different lengths.
+pub fn compress_batch(points: &[GeP3], out: &mut [[u8; 32]]) {
+    assert_eq!(points.len(), out.len());
+
+    for (points, out) in points
+        .chunks(BATCH_INVERT_CHUNK)
+        .zip(out.chunks_mut(BATCH_INVERT_CHUNK))
+    {
+        let mut recips = [FE_ONE; BATCH_INVERT_CHUNK];
+        for (recip, point) in recips.iter_mut().zip(points) {
+            *recip = point.z;
         }
-    }
-}
-
-impl Sub<GePrecomp> for GeP3 {
-    type Output = GeP1P1;
+        FieldElement::batch_invert(&mut recips[..points.len()]);
 
-    fn sub(self, _rhs: GePrecomp) -> GeP1P1 {
-        let y1_plus_x1 = self.y + self.x;
-        let y1_minus_x1 = self.y - self.x;
-        let a = y1_plus_x1 * _rhs.y_minus_x;
-        let b = y1_minus_x1 * _rhs.y_plus_x;
-        let c = _rhs.xy2d * self.t;
-        let d = self.z + self.z;
-        let x3 = a - b;
-        let y3 = a + b;
-        let z3 = d - c;
-        let t3 = d + c;
-
-        GeP1P1 {
-            x: x3,
-            y: y3,
-            z: z3,
-            t: t3,
+        for ((bytes, point), recip) in out.iter_mut().zip(points).zip(&recips) {
+            let x = point.x * *recip;
+            *bytes = (point.y * *recip).to_bytes();
+            bytes[31] ^= u8::from(x.is_negative()) << 7;
         }
     }
 }
 
-#[inline]
-fn equal(b: u8, c: u8) -> i32 {
-    let x = b ^ c; // 0: yes; 1..255: no
-    let mut y = u32::from(x); // 0: yes; 1..255: no
-    y = y.wrapping_sub(1); // 4294967295: yes; 0..254: no
-    y >>= 31; // 1: yes; 0: no
-    y as i32
-}
-
-#[inline]
-fn negative(b: i8) -> u8 {
-    let mut x = i64::from(b) as u64;
-    x >>= 63; // 1: yes; 0: no
-    x as u8
-}
-
-impl GePrecomp {
-    fn zero() -> GePrecomp {
-        GePrecomp {
-            y_plus_x: FE_ONE,
-            y_minus_x: FE_ONE,
-            xy2d: FE_ZERO,
-        }
-    }
-
-    pub fn maybe_set(&mut self, other: &GePrecomp, do_swap: i32) {
-        self.y_plus_x.maybe_set(&other.y_plus_x, do_swap);
-        self.y_minus_x.maybe_set(&other.y_minus_x, do_swap);
-        self.xy2d.maybe_set(&other.xy2d, do_swap);
-    }
-
-    pub fn select(pos: usize, b: i8) -> GePrecomp {
-        let bnegative: u8 = negative(b);
-        let babs: u8 = (b - (((-(bnegative as i8)) & b) << 1)) as u8;
-        let mut t = GePrecomp::zero();
-        t.maybe_set(&GE_PRECOMP_BASE[pos][0], equal(babs, 1));
-        t.maybe_set(&GE_PRECOMP_BASE[pos][1], equal(babs, 2));
-        t.maybe_set(&GE_PRECOMP_BASE[pos][2], equal(babs, 3));
-        t.maybe_set(&GE_PRECOMP_BASE[pos][3], equal(babs, 4));
-        t.maybe_set(&GE_PRECOMP_BASE[pos][4], equal(babs, 5));
-        t.maybe_set(&GE_PRECOMP_BASE[pos][5], equal(babs, 6));
-        t.maybe_set(&GE_PRECOMP_BASE[pos][6], equal(babs, 7));
-        t.maybe_set(&GE_PRECOMP_BASE[pos][7], equal(babs, 8));
-        let minus_t = GePrecomp {
-            y_plus_x: t.y_minus_x,
-            y_minus_x: t.y_plus_x,
-            xy2d: t.xy2d.neg(),
-        };
-        t.maybe_set(&minus_t, i32::from(bnegative));
-        t
-    }
+/// Reduces a 64-byte little-endian integer modulo the group order
+/// `l = 2^252 + 27742317777372353535851937790883648493`.
+///
+/// This is how a SHA-512 digest is turned into a scalar.
+#[must_use]
+pub fn scalar_reduce(wide: [u8; 64]) -> [u8; 32] {
+    let mut s = wide;
+    sc_reduce(&mut s);
+    let mut reduced = [0u8; 32];
+    reduced.copy_from_slice(&s[..32]);
+    reduced
 }
 
-// h = a * B
-// where a = a[0]+256*a[1]+...+256^31 a[31]
-// B is the Ed25519 base point (x,4/5) with x positive.
-//
-// Preconditions:
-//   a[31] <= 127
-#[doc(hidden)]
-pub fn ge_scalarmult_base(a: &[u8]) -> GeP3 {
-    let mut es: [i8; 64] = [0; 64];
-    let mut r: GeP1P1;
-    let mut s: GeP2;
-    let mut t: GePrecomp;
-
-    for i in 0..32 {
-        es[2 * i] = (a[i] & 15) as i8;
-        es[2 * i + 1] = ((a[i] >> 4) & 15) as i8;
-    }
-    // each es[i] is between 0 and 15
-    // es[63] is between 0 and 7
-
-    let mut carry: i8 = 0;
-    for i in es.iter_mut().take(63) {
-        *i += carry;
-        carry = *i + 8;
-        carry >>= 4;
-        *i -= carry << 4;
-    }
-    es[63] += carry;
-    // each es[i] is between -8 and 8
-
-    let mut h = GeP3::zero();
-    for i in (1..64).step_by(2) {
-        t = GePrecomp::select(i / 2, es[i]);
-        r = h + t;
-        h = r.to_p3();
-    }
-
-    r = h.dbl();
-    s = r.to_p2();
-    r = s.dbl();
-    s = r.to_p2();
-    r = s.dbl();
-    s = r.to_p2();
-    r = s.dbl();
-    h = r.to_p3();
-
-    for i in (0..64).step_by(2) {
-        t = GePrecomp::select(i / 2, es[i]);
-        r = h + t;
-        h = r.to_p3();
-    }
-
-    h
-}
-// Input:
-//     s[0]+256*s[1]+...+256^63*s[63] = s
-//
-// Output:
-//     s[0]+256*s[1]+...+256^31*s[31] = s mod l
-//     where l = 2^252 + `27742317777372353535851937790883648493`.
-//     Overwrites s in place.
-#[doc(hidden)]
-pub fn sc_reduce(s: &mut [u8]) {
-    let mut s0: i64 = 2_097_151 & load_3i(s);
-    let mut s1: i64 = 2_097_151 & (load_4i(&s[2..6]) >> 5);
-    let mut s2: i64 = 2_097_151 & (load_3i(&s[5..8]) >> 2);
-    let mut s3: i64 = 2_097_151 & (load_4i(&s[7..11]) >> 7);
-    let mut s4: i64 = 2_097_151 & (load_4i(&s[10..14]) >> 4);
-    let mut s5: i64 = 2_097_151 & (load_3i(&s[13..16]) >> 1);
-    let mut s6: i64 = 2_097_151 & (load_4i(&s[15..19]) >> 6);
-    let mut s7: i64 = 2_097_151 & (load_3i(&s[18..21]) >> 3);
-    let mut s8: i64 = 2_097_151 & load_3i(&s[21..24]);
-    let mut s9: i64 = 2_097_151 & (load_4i(&s[23..27]) >> 5);
-    let mut s10: i64 = 2_097_151 & (load_3i(&s[26..29]) >> 2);
-    let mut s11: i64 = 2_097_151 & (load_4i(&s[28..32]) >> 7);
-    let mut s12: i64 = 2_097_151 & (load_4i(&s[31..35]) >> 4);
-    let mut s13: i64 = 2_097_151 & (load_3i(&s[34..37]) >> 1);
-    let mut s14: i64 = 2_097_151 & (load_4i(&s[36..40]) >> 6);
-    let mut s15: i64 = 2_097_151 & (load_3i(&s[39..42]) >> 3);
-    let mut s16: i64 = 2_097_151 & load_3i(&s[42..45]);
-    let mut s17: i64 = 2_097_151 & (load_4i(&s[44..48]) >> 5);
-    let s18: i64 = 2_097_151 & (load_3i(&s[47..50]) >> 2);
-    let s19: i64 = 2_097_151 & (load_4i(&s[49..53]) >> 7);
-    let s20: i64 = 2_097_151 & (load_4i(&s[52..56]) >> 4);
-    let s21: i64 = 2_097_151 & (load_3i(&s[55..58]) >> 1);
-    let s22: i64 = 2_097_151 & (load_4i(&s[57..61]) >> 6);
-    let s23: i64 = load_4i(&s[60..64]) >> 3;
-    let mut carry0: i64;
-    let mut carry1: i64;
-    let mut carry2: i64;
-    let mut carry3: i64;
-    let mut carry4: i64;
-    let mut carry5: i64;
-    let mut carry6: i64;
-    let mut carry7: i64;
-    let mut carry8: i64;
-    let mut carry9: i64;
-    let mut carry10: i64;
-    let mut carry11: i64;
-    let carry12: i64;
-    let carry13: i64;
-    let carry14: i64;
-    let carry15: i64;
-    let carry16: i64;
-
-    s11 += s23 * 666_643;
-    s12 += s23 * 470_296;
-    s13 += s23 * 654_183;
-    s14 -= s23 * 997_805;
-    s15 += s23 * 136_657;
-    s16 -= s23 * 683_901;
-
-    s10 += s22 * 666_643;
-    s11 += s22 * 470_296;
-    s12 += s22 * 654_183;
-    s13 -= s22 * 997_805;
-    s14 += s22 * 136_657;
-    s15 -= s22 * 683_901;
-
-    s9 += s21 * 666_643;
-    s10 += s21 * 470_296;
-    s11 += s21 * 654_183;
-    s12 -= s21 * 997_805;
-    s13 += s21 * 136_657;
-    s14 -= s21 * 683_901;
-
-    s8 += s20 * 666_643;
-    s9 += s20 * 470_296;
-    s10 += s20 * 654_183;
-    s11 -= s20 * 997_805;
-    s12 += s20 * 136_657;
-    s13 -= s20 * 683_901;
-
-    s7 += s19 * 666_643;
-    s8 += s19 * 470_296;
-    s9 += s19 * 654_183;
-    s10 -= s19 * 997_805;
-    s11 += s19 * 136_657;
-    s12 -= s19 * 683_901;
-
-    s6 += s18 * 666_643;
-    s7 += s18 * 470_296;
-    s8 += s18 * 654_183;
-    s9 -= s18 * 997_805;
-    s10 += s18 * 136_657;
-    s11 -= s18 * 683_901;
-
-    carry6 = (s6 + (1 << 20)) >> 21;
-    s7 += carry6;
-    s6 -= carry6 << 21;
-    carry8 = (s8 + (1 << 20)) >> 21;
-    s9 += carry8;
-    s8 -= carry8 << 21;
-    carry10 = (s10 + (1 << 20)) >> 21;
-    s11 += carry10;
-    s10 -= carry10 << 21;
-    carry12 = (s12 + (1 << 20)) >> 21;
-    s13 += carry12;
-    s12 -= carry12 << 21;
-    carry14 = (s14 + (1 << 20)) >> 21;
-    s15 += carry14;
-    s14 -= carry14 << 21;
-    carry16 = (s16 + (1 << 20)) >> 21;
-    s17 += carry16;
-    s16 -= carry16 << 21;
-
-    carry7 = (s7 + (1 << 20)) >> 21;
-    s8 += carry7;
-    s7 -= carry7 << 21;
-    carry9 = (s9 + (1 << 20)) >> 21;
-    s10 += carry9;
-    s9 -= carry9 << 21;
-    carry11 = (s11 + (1 << 20)) >> 21;
-    s12 += carry11;
-    s11 -= carry11 << 21;
-    carry13 = (s13 + (1 << 20)) >> 21;
-    s14 += carry13;
-    s13 -= carry13 << 21;
-    carry15 = (s15 + (1 << 20)) >> 21;
-    s16 += carry15;
-    s15 -= carry15 << 21;
-
-    s5 += s17 * 666_643;
-    s6 += s17 * 470_296;
-    s7 += s17 * 654_183;
-    s8 -= s17 * 997_805;
-    s9 += s17 * 136_657;
-    s10 -= s17 * 683_901;
-
-    s4 += s16 * 666_643;
-    s5 += s16 * 470_296;
-    s6 += s16 * 654_183;
-    s7 -= s16 * 997_805;
-    s8 += s16 * 136_657;
-    s9 -= s16 * 683_901;
-
-    s3 += s15 * 666_643;
-    s4 += s15 * 470_296;
-    s5 += s15 * 654_183;
-    s6 -= s15 * 997_805;
-    s7 += s15 * 136_657;
-    s8 -= s15 * 683_901;
-
-    s2 += s14 * 666_643;
-    s3 += s14 * 470_296;
-    s4 += s14 * 654_183;
-    s5 -= s14 * 997_805;
-    s6 += s14 * 136_657;
-    s7 -= s14 * 683_901;
-
-    s1 += s13 * 666_643;
-    s2 += s13 * 470_296;
-    s3 += s13 * 654_183;
-    s4 -= s13 * 997_805;
-    s5 += s13 * 136_657;
-    s6 -= s13 * 683_901;
-
-    s0 += s12 * 666_643;
-    s1 += s12 * 470_296;
-    s2 += s12 * 654_183;
-    s3 -= s12 * 997_805;
-    s4 += s12 * 136_657;
-    s5 -= s12 * 683_901;
-    s12 = 0;
-
-    carry0 = (s0 + (1 << 20)) >> 21;
-    s1 += carry0;
-    s0 -= carry0 << 21;
-    carry2 = (s2 + (1 << 20)) >> 21;
-    s3 += carry2;
-    s2 -= carry2 << 21;
-    carry4 = (s4 + (1 << 20)) >> 21;
-    s5 += carry4;
-    s4 -= carry4 << 21;
-    carry6 = (s6 + (1 << 20)) >> 21;
-    s7 += carry6;
-    s6 -= carry6 << 21;
-    carry8 = (s8 + (1 << 20)) >> 21;
-    s9 += carry8;
-    s8 -= carry8 << 21;
-    carry10 = (s10 + (1 << 20)) >> 21;
-    s11 += carry10;
-    s10 -= carry10 << 21;
-
-    carry1 = (s1 + (1 << 20)) >> 21;
-    s2 += carry1;
-    s1 -= carry1 << 21;
-    carry3 = (s3 + (1 << 20)) >> 21;
-    s4 += carry3;
-    s3 -= carry3 << 21;
-    carry5 = (s5 + (1 << 20)) >> 21;
-    s6 += carry5;
-    s5 -= carry5 << 21;
-    carry7 = (s7 + (1 << 20)) >> 21;
-    s8 += carry7;
-    s7 -= carry7 << 21;
-    carry9 = (s9 + (1 << 20)) >> 21;
-    s10 += carry9;
-    s9 -= carry9 << 21;
-    carry11 = (s11 + (1 << 20)) >> 21;
-    s12 += carry11;
-    s11 -= carry11 << 21;
-
-    s0 += s12 * 666_643;
-    s1 += s12 * 470_296;
-    s2 += s12 * 654_183;
-    s3 -= s12 * 997_805;
-    s4 += s12 * 136_657;
-    s5 -= s12 * 683_901;
-    s12 = 0;
-
-    carry0 = s0 >> 21;
-    s1 += carry0;
-    s0 -= carry0 << 21;
-    carry1 = s1 >> 21;
-    s2 += carry1;
-    s1 -= carry1 << 21;
-    carry2 = s2 >> 21;
-    s3 += carry2;
-    s2 -= carry2 << 21;
-    carry3 = s3 >> 21;
-    s4 += carry3;
-    s3 -= carry3 << 21;
-    carry4 = s4 >> 21;
-    s5 += carry4;
-    s4 -= carry4 << 21;
-    carry5 = s5 >> 21;
-    s6 += carry5;
-    s5 -= carry5 << 21;
-    carry6 = s6 >> 21;
-    s7 += carry6;
-    s6 -= carry6 << 21;
-    carry7 = s7 >> 21;
-    s8 += carry7;
-    s7 -= carry7 << 21;
-    carry8 = s8 >> 21;
-    s9 += carry8;
-    s8 -= carry8 << 21;
-    carry9 = s9 >> 21;
-    s10 += carry9;
-    s9 -= carry9 << 21;
-    carry10 = s10 >> 21;
-    s11 += carry10;
-    s10 -= carry10 << 21;
-    carry11 = s11 >> 21;
-    s12 += carry11;
-    s11 -= carry11 << 21;
-
-    s0 += s12 * 666_643;
-    s1 += s12 * 470_296;
-    s2 += s12 * 654_183;
-    s3 -= s12 * 997_805;
-    s4 += s12 * 136_657;
-    s5 -= s12 * 683_901;
-
-    carry0 = s0 >> 21;
-    s1 += carry0;
-    s0 -= carry0 << 21;
-    carry1 = s1 >> 21;
-    s2 += carry1;
-    s1 -= carry1 << 21;
-    carry2 = s2 >> 21;
-    s3 += carry2;
-    s2 -= carry2 << 21;
-    carry3 = s3 >> 21;
-    s4 += carry3;
-    s3 -= carry3 << 21;
-    carry4 = s4 >> 21;
-    s5 += carry4;
-    s4 -= carry4 << 21;
-    carry5 = s5 >> 21;
-    s6 += carry5;
-    s5 -= carry5 << 21;
-    carry6 = s6 >> 21;
-    s7 += carry6;
-    s6 -= carry6 << 21;
-    carry7 = s7 >> 21;
-    s8 += carry7;
-    s7 -= carry7 << 21;
-    carry8 = s8 >> 21;
-    s9 += carry8;
-    s8 -= carry8 << 21;
-    carry9 = s9 >> 21;
-    s10 += carry9;
-    s9 -= carry9 << 21;
-    carry10 = s10 >> 21;
-    s11 += carry10;
-    s10 -= carry10 << 21;
-
-    s[0] = s0 as u8;
-    s[1] = (s0 >> 8) as u8;
-    s[2] = ((s0 >> 16) | (s1 << 5)) as u8;
-    s[3] = (s1 >> 3) as u8;
-    s[4] = (s1 >> 11) as u8;
-    s[5] = ((s1 >> 19) | (s2 << 2)) as u8;
-    s[6] = (s2 >> 6) as u8;
-    s[7] = ((s2 >> 14) | (s3 << 7)) as u8;
-    s[8] = (s3 >> 1) as u8;
-    s[9] = (s3 >> 9) as u8;
-    s[10] = ((s3 >> 17) | (s4 << 4)) as u8;
-    s[11] = (s4 >> 4) as u8;
-    s[12] = (s4 >> 12) as u8;
-    s[13] = ((s4 >> 20) | (s5 << 1)) as u8;
-    s[14] = (s5 >> 7) as u8;
-    s[15] = ((s5 >> 15) | (s6 << 6)) as u8;
-    s[16] = (s6 >> 2) as u8;
-    s[17] = (s6 >> 10) as u8;
-    s[18] = ((s6 >> 18) | (s7 << 3)) as u8;
-    s[19] = (s7 >> 5) as u8;
-    s[20] = (s7 >> 13) as u8;
-    s[21] = s8 as u8;
-    s[22] = (s8 >> 8) as u8;
-    s[23] = ((s8 >> 16) | (s9 << 5)) as u8;
-    s[24] = (s9 >> 3) as u8;
-    s[25] = (s9 >> 11) as u8;
-    s[26] = ((s9 >> 19) | (s10 << 2)) as u8;
-    s[27] = (s10 >> 6) as u8;
-    s[28] = ((s10 >> 14) | (s11 << 7)) as u8;
-    s[29] = (s11 >> 1) as u8;
-    s[30] = (s11 >> 9) as u8;
-    s[31] = (s11 >> 17) as u8;
+/// Computes `a * b + c` modulo the group order `l`, all three inputs and
+/// the result being 32-byte little-endian integers.
+#[must_use]
+pub fn scalar_muladd(a: &[u8; 32], b: &[u8; 32], c: &[u8; 32]) -> [u8; 32] {
+    let mut s = [0u8; 32];
+    sc_muladd(&mut s, a, b, c);
+    s
 }
 
-// Input:
-//     a[0]+256*a[1]+...+256^31*a[31] = a
-//     b[0]+256*b[1]+...+256^31*b[31] = b
-//     c[0]+256*c[1]+...+256^31*c[31] = c
-//
-// Output:
-//     s[0]+256*s[1]+...+256^31*s[31] = (ab+c) mod l
-//     where l = 2^252 + 27742317777372353535851937790883648493.
-#[doc(hidden)]
-pub fn sc_muladd(s: &mut [u8], a: &[u8], b: &[u8], c: &[u8]) {
-    let a0 = 2_097_151 & load_3i(&a[0..3]);
-    let a1 = 2_097_151 & (load_4i(&a[2..6]) >> 5);
-    let a2 = 2_097_151 & (load_3i(&a[5..8]) >> 2);
-    let a3 = 2_097_151 & (load_4i(&a[7..11]) >> 7);
-    let a4 = 2_097_151 & (load_4i(&a[10..14]) >> 4);
-    let a5 = 2_097_151 & (load_3i(&a[13..16]) >> 1);
-    let a6 = 2_097_151 & (load_4i(&a[15..19]) >> 6);
-    let a7 = 2_097_151 & (load_3i(&a[18..21]) >> 3);
-    let a8 = 2_097_151 & load_3i(&a[21..24]);
-    let a9 = 2_097_151 & (load_4i(&a[23..27]) >> 5);
-    let a10 = 2_097_151 & (load_3i(&a[26..29]) >> 2);
-    let a11 = load_4i(&a[28..32]) >> 7;
-    let b0 = 2_097_151 & load_3i(&b[0..3]);
-    let b1 = 2_097_151 & (load_4i(&b[2..6]) >> 5);
-    let b2 = 2_097_151 & (load_3i(&b[5..8]) >> 2);
-    let b3 = 2_097_151 & (load_4i(&b[7..11]) >> 7);
-    let b4 = 2_097_151 & (load_4i(&b[10..14]) >> 4);
-    let b5 = 2_097_151 & (load_3i(&b[13..16]) >> 1);
-    let b6 = 2_097_151 & (load_4i(&b[15..19]) >> 6);
-    let b7 = 2_097_151 & (load_3i(&b[18..21]) >> 3);
-    let b8 = 2_097_151 & load_3i(&b[21..24]);
-    let b9 = 2_097_151 & (load_4i(&b[23..27]) >> 5);
-    let b10 = 2_097_151 & (load_3i(&b[26..29]) >> 2);
-    let b11 = load_4i(&b[28..32]) >> 7;
-    let c0 = 2_097_151 & load_3i(&c[0..3]);
-    let c1 = 2_097_151 & (load_4i(&c[2..6]) >> 5);
-    let c2 = 2_097_151 & (load_3i(&c[5..8]) >> 2);
-    let c3 = 2_097_151 & (load_4i(&c[7..11]) >> 7);
-    let c4 = 2_097_151 & (load_4i(&c[10..14]) >> 4);
-    let c5 = 2_097_151 & (load_3i(&c[13..16]) >> 1);
-    let c6 = 2_097_151 & (load_4i(&c[15..19]) >> 6);
-    let c7 = 2_097_151 & (load_3i(&c[18..21]) >> 3);
-    let c8 = 2_097_151 & load_3i(&c[21..24]);
-    let c9 = 2_097_151 & (load_4i(&c[23..27]) >> 5);
-    let c10 = 2_097_151 & (load_3i(&c[26..29]) >> 2);
-    let c11 = load_4i(&c[28..32]) >> 7;
-    let mut s0: i64;
-    let mut s1: i64;
-    let mut s2: i64;
-    let mut s3: i64;
-    let mut s4: i64;
-    let mut s5: i64;
-    let mut s6: i64;
-    let mut s7: i64;
-    let mut s8: i64;
-    let mut s9: i64;
-    let mut s10: i64;
-    let mut s11: i64;
-    let mut s12: i64;
-    let mut s13: i64;
-    let mut s14: i64;
-    let mut s15: i64;
-    let mut s16: i64;
-    let mut s17: i64;
-    let mut s18: i64;
-    let mut s19: i64;
-    let mut s20: i64;
-    let mut s21: i64;
-    let mut s22: i64;
-    let mut s23: i64;
-    let mut carry0: i64;
-    let mut carry1: i64;
-    let mut carry2: i64;
-    let mut carry3: i64;
-    let mut carry4: i64;
-    let mut carry5: i64;
-    let mut carry6: i64;
-    let mut carry7: i64;
-    let mut carry8: i64;
-    let mut carry9: i64;
-    let mut carry10: i64;
-    let mut carry11: i64;
-    let mut carry12: i64;
-    let mut carry13: i64;
-    let mut carry14: i64;
-    let mut carry15: i64;
-    let mut carry16: i64;
-    let carry17: i64;
-    let carry18: i64;
-    let carry19: i64;
-    let carry20: i64;
-    let carry21: i64;
-    let carry22: i64;
-
-    s0 = c0 + a0 * b0;
-    s1 = c1 + a0 * b1 + a1 * b0;
-    s2 = c2 + a0 * b2 + a1 * b1 + a2 * b0;
-    s3 = c3 + a0 * b3 + a1 * b2 + a2 * b1 + a3 * b0;
-    s4 = c4 + a0 * b4 + a1 * b3 + a2 * b2 + a3 * b1 + a4 * b0;
-    s5 = c5 + a0 * b5 + a1 * b4 + a2 * b3 + a3 * b2 + a4 * b1 + a5 * b0;
-    s6 = c6
-        + a0 * b6
-        + a1 * b5
-        + a2 * b4
-        + a3 * b3
-        + a4 * b2
-        + a5 * b1
-        + a6 * b0;
-    s7 = c7
-        + a0 * b7
-        + a1 * b6
-        + a2 * b5
-        + a3 * b4
-        + a4 * b3
-        + a5 * b2
-        + a6 * b1
-        + a7 * b0;
-    s8 = c8
-        + a0 * b8
-        + a1 * b7
-        + a2 * b6
-        + a3 * b5
-        + a4 * b4
-        + a5 * b3
-        + a6 * b2
-        + a7 * b1
-        + a8 * b0;
-    s9 = c9
-        + a0 * b9
-        + a1 * b8
-        + a2 * b7
-        + a3 * b6
-        + a4 * b5
-        + a5 * b4
-        + a6 * b3
-        + a7 * b2
-        + a8 * b1
-        + a9 * b0;
-    s10 = c10
-        + a0 * b10
-        + a1 * b9
-        + a2 * b8
-        + a3 * b7
-        + a4 * b6
-        + a5 * b5
-        + a6 * b4
-        + a7 * b3
-        + a8 * b2
-        + a9 * b1
-        + a10 * b0;
-    s11 = c11
-        + a0 * b11
-        + a1 * b10
-        + a2 * b9
-        + a3 * b8
-        + a4 * b7
-        + a5 * b6
-        + a6 * b5
-        + a7 * b4
-        + a8 * b3
-        + a9 * b2
-        + a10 * b1
-        + a11 * b0;
-    s12 = a1 * b11
-        + a2 * b10
-        + a3 * b9
-        + a4 * b8
-        + a5 * b7
-        + a6 * b6
-        + a7 * b5
-        + a8 * b4
-        + a9 * b3
-        + a10 * b2
-        + a11 * b1;
-    s13 = a2 * b11
-        + a3 * b10
-        + a4 * b9
-        + a5 * b8
-        + a6 * b7
-        + a7 * b6
-        + a8 * b5
-        + a9 * b4
-        + a10 * b3
-        + a11 * b2;
-    s14 = a3 * b11
-        + a4 * b10
-        + a5 * b9
-        + a6 * b8
-        + a7 * b7
-        + a8 * b6
-        + a9 * b5
-        + a10 * b4
-        + a11 * b3;
-    s15 = a4 * b11
-        + a5 * b10
-        + a6 * b9
-        + a7 * b8
-        + a8 * b7
-        + a9 * b6
-        + a10 * b5
-        + a11 * b4;
-    s16 =
-        a5 * b11 + a6 * b10 + a7 * b9 + a8 * b8 + a9 * b7 + a10 * b6 + a11 * b5;
-    s17 = a6 * b11 + a7 * b10 + a8 * b9 + a9 * b8 + a10 * b7 + a11 * b6;
-    s18 = a7 * b11 + a8 * b10 + a9 * b9 + a10 * b8 + a11 * b7;
-    s19 = a8 * b11 + a9 * b10 + a10 * b9 + a11 * b8;
-    s20 = a9 * b11 + a10 * b10 + a11 * b9;
-    s21 = a10 * b11 + a11 * b10;
-    s22 = a11 * b11;
-    s23 = 0;
-
-    carry0 = (s0 + (1 << 20)) >> 21;
-    s1 += carry0;
-    s0 -= carry0 << 21;
-    carry2 = (s2 + (1 << 20)) >> 21;
-    s3 += carry2;
-    s2 -= carry2 << 21;
-    carry4 = (s4 + (1 << 20)) >> 21;
-    s5 += carry4;
-    s4 -= carry4 << 21;
-    carry6 = (s6 + (1 << 20)) >> 21;
-    s7 += carry6;
-    s6 -= carry6 << 21;
-    carry8 = (s8 + (1 << 20)) >> 21;
-    s9 += carry8;
-    s8 -= carry8 << 21;
-    carry10 = (s10 + (1 << 20)) >> 21;
-    s11 += carry10;
-    s10 -= carry10 << 21;
-    carry12 = (s12 + (1 << 20)) >> 21;
-    s13 += carry12;
-    s12 -= carry12 << 21;
-    carry14 = (s14 + (1 << 20)) >> 21;
-    s15 += carry14;
-    s14 -= carry14 << 21;
-    carry16 = (s16 + (1 << 20)) >> 21;
-    s17 += carry16;
-    s16 -= carry16 << 21;
-    carry18 = (s18 + (1 << 20)) >> 21;
-    s19 += carry18;
-    s18 -= carry18 << 21;
-    carry20 = (s20 + (1 << 20)) >> 21;
-    s21 += carry20;
-    s20 -= carry20 << 21;
-    carry22 = (s22 + (1 << 20)) >> 21;
-    s23 += carry22;
-    s22 -= carry22 << 21;
-
-    carry1 = (s1 + (1 << 20)) >> 21;
-    s2 += carry1;
-    s1 -= carry1 << 21;
-    carry3 = (s3 + (1 << 20)) >> 21;
-    s4 += carry3;
-    s3 -= carry3 << 21;
-    carry5 = (s5 + (1 << 20)) >> 21;
-    s6 += carry5;
-    s5 -= carry5 << 21;
-    carry7 = (s7 + (1 << 20)) >> 21;
-    s8 += carry7;
-    s7 -= carry7 << 21;
-    carry9 = (s9 + (1 << 20)) >> 21;
-    s10 += carry9;
-    s9 -= carry9 << 21;
-    carry11 = (s11 + (1 << 20)) >> 21;
-    s12 += carry11;
-    s11 -= carry11 << 21;
-    carry13 = (s13 + (1 << 20)) >> 21;
-    s14 += carry13;
-    s13 -= carry13 << 21;
-    carry15 = (s15 + (1 << 20)) >> 21;
-    s16 += carry15;
-    s15 -= carry15 << 21;
-    carry17 = (s17 + (1 << 20)) >> 21;
-    s18 += carry17;
-    s17 -= carry17 << 21;
-    carry19 = (s19 + (1 << 20)) >> 21;
-    s20 += carry19;
-    s19 -= carry19 << 21;
-    carry21 = (s21 + (1 << 20)) >> 21;
-    s22 += carry21;
-    s21 -= carry21 << 21;
-
-    s11 += s23 * 666_643;
-    s12 += s23 * 470_296;
-    s13 += s23 * 654_183;
-    s14 -= s23 * 997_805;
-    s15 += s23 * 136_657;
-    s16 -= s23 * 683_901;
-
-    s10 += s22 * 666_643;
-    s11 += s22 * 470_296;
-    s12 += s22 * 654_183;
-    s13 -= s22 * 997_805;
-    s14 += s22 * 136_657;
-    s15 -= s22 * 683_901;
-
-    s9 += s21 * 666_643;
-    s10 += s21 * 470_296;
-    s11 += s21 * 654_183;
-    s12 -= s21 * 997_805;
-    s13 += s21 * 136_657;
-    s14 -= s21 * 683_901;
-
-    s8 += s20 * 666_643;
-    s9 += s20 * 470_296;
-    s10 += s20 * 654_183;
-    s11 -= s20 * 997_805;
-    s12 += s20 * 136_657;
-    s13 -= s20 * 683_901;
-
-    s7 += s19 * 666_643;
-    s8 += s19 * 470_296;
-    s9 += s19 * 654_183;
-    s10 -= s19 * 997_805;
-    s11 += s19 * 136_657;
-    s12 -= s19 * 683_901;
-
-    s6 += s18 * 666_643;
-    s7 += s18 * 470_296;
-    s8 += s18 * 654_183;
-    s9 -= s18 * 997_805;
-    s10 += s18 * 136_657;
-    s11 -= s18 * 683_901;
-
-    carry6 = (s6 + (1 << 20)) >> 21;
-    s7 += carry6;
-    s6 -= carry6 << 21;
-    carry8 = (s8 + (1 << 20)) >> 21;
-    s9 += carry8;
-    s8 -= carry8 << 21;
-    carry10 = (s10 + (1 << 20)) >> 21;
-    s11 += carry10;
-    s10 -= carry10 << 21;
-    carry12 = (s12 + (1 << 20)) >> 21;
-    s13 += carry12;
-    s12 -= carry12 << 21;
-    carry14 = (s14 + (1 << 20)) >> 21;
-    s15 += carry14;
-    s14 -= carry14 << 21;
-    carry16 = (s16 + (1 << 20)) >> 21;
-    s17 += carry16;
-    s16 -= carry16 << 21;
-
-    carry7 = (s7 + (1 << 20)) >> 21;
-    s8 += carry7;
-    s7 -= carry7 << 21;
-    carry9 = (s9 + (1 << 20)) >> 21;
-    s10 += carry9;
-    s9 -= carry9 << 21;
-    carry11 = (s11 + (1 << 20)) >> 21;
-    s12 += carry11;
-    s11 -= carry11 << 21;
-    carry13 = (s13 + (1 << 20)) >> 21;
-    s14 += carry13;
-    s13 -= carry13 << 21;
-    carry15 = (s15 + (1 << 20)) >> 21;
-    s16 += carry15;
-    s15 -= carry15 << 21;
-
-    s5 += s17 * 666_643;
-    s6 += s17 * 470_296;
-    s7 += s17 * 654_183;
-    s8 -= s17 * 997_805;
-    s9 += s17 * 136_657;
-    s10 -= s17 * 683_901;
-
-    s4 += s16 * 666_643;
-    s5 += s16 * 470_296;
-    s6 += s16 * 654_183;
-    s7 -= s16 * 997_805;
-    s8 += s16 * 136_657;
-    s9 -= s16 * 683_901;
-
-    s3 += s15 * 666_643;
-    s4 += s15 * 470_296;
-    s5 += s15 * 654_183;
-    s6 -= s15 * 997_805;
-    s7 += s15 * 136_657;
-    s8 -= s15 * 683_901;
-
-    s2 += s14 * 666_643;
-    s3 += s14 * 470_296;
-    s4 += s14 * 654_183;
-    s5 -= s14 * 997_805;
-    s6 += s14 * 136_657;
-    s7 -= s14 * 683_901;
-
-    s1 += s13 * 666_643;
-    s2 += s13 * 470_296;
-    s3 += s13 * 654_183;
-    s4 -= s13 * 997_805;
-    s5 += s13 * 136_657;
-    s6 -= s13 * 683_901;
-
-    s0 += s12 * 666_643;
-    s1 += s12 * 470_296;
-    s2 += s12 * 654_183;
-    s3 -= s12 * 997_805;
-    s4 += s12 * 136_657;
-    s5 -= s12 * 683_901;
-    s12 = 0;
-
-    carry0 = (s0 + (1 << 20)) >> 21;
-    s1 += carry0;
-    s0 -= carry0 << 21;
-    carry2 = (s2 + (1 << 20)) >> 21;
-    s3 += carry2;
-    s2 -= carry2 << 21;
-    carry4 = (s4 + (1 << 20)) >> 21;
-    s5 += carry4;
-    s4 -= carry4 << 21;
-    carry6 = (s6 + (1 << 20)) >> 21;
-    s7 += carry6;
-    s6 -= carry6 << 21;
-    carry8 = (s8 + (1 << 20)) >> 21;
-    s9 += carry8;
-    s8 -= carry8 << 21;
-    carry10 = (s10 + (1 << 20)) >> 21;
-    s11 += carry10;
-    s10 -= carry10 << 21;
-
-    carry1 = (s1 + (1 << 20)) >> 21;
-    s2 += carry1;
-    s1 -= carry1 << 21;
-    carry3 = (s3 + (1 << 20)) >> 21;
-    s4 += carry3;
-    s3 -= carry3 << 21;
-    carry5 = (s5 + (1 << 20)) >> 21;
-    s6 += carry5;
-    s5 -= carry5 << 21;
-    carry7 = (s7 + (1 << 20)) >> 21;
-    s8 += carry7;
-    s7 -= carry7 << 21;
-    carry9 = (s9 + (1 << 20)) >> 21;
-    s10 += carry9;
-    s9 -= carry9 << 21;
-    carry11 = (s11 + (1 << 20)) >> 21;
-    s12 += carry11;
-    s11 -= carry11 << 21;
-
-    s0 += s12 * 666_643;
-    s1 += s12 * 470_296;
-    s2 += s12 * 654_183;
-    s3 -= s12 * 997_805;
-    s4 += s12 * 136_657;
-    s5 -= s12 * 683_901;
-    s12 = 0;
-
-    carry0 = s0 >> 21;
-    s1 += carry0;
-    s0 -= carry0 << 21;
-    carry1 = s1 >> 21;
-    s2 += carry1;
-    s1 -= carry1 << 21;
-    carry2 = s2 >> 21;
-    s3 += carry2;
-    s2 -= carry2 << 21;
-    carry3 = s3 >> 21;
-    s4 += carry3;
-    s3 -= carry3 << 21;
-    carry4 = s4 >> 21;
-    s5 += carry4;
-    s4 -= carry4 << 21;
-    carry5 = s5 >> 21;
-    s6 += carry5;
-    s5 -= carry5 << 21;
-    carry6 = s6 >> 21;
-    s7 += carry6;
-    s6 -= carry6 << 21;
-    carry7 = s7 >> 21;
-    s8 += carry7;
-    s7 -= carry7 << 21;
-    carry8 = s8 >> 21;
-    s9 += carry8;
-    s8 -= carry8 << 21;
-    carry9 = s9 >> 21;
-    s10 += carry9;
-    s9 -= carry9 << 21;
-    carry10 = s10 >> 21;
-    s11 += carry10;
-    s10 -= carry10 << 21;
-    carry11 = s11 >> 21;
-    s12 += carry11;
-    s11 -= carry11 << 21;
-
-    s0 += s12 * 666_643;
-    s1 += s12 * 470_296;
-    s2 += s12 * 654_183;
-    s3 -= s12 * 997_805;
-    s4 += s12 * 136_657;
-    s5 -= s12 * 683_901;
-
-    carry0 = s0 >> 21;
-    s1 += carry0;
-    s0 -= carry0 << 21;
-    carry1 = s1 >> 21;
-    s2 += carry1;
-    s1 -= carry1 << 21;
-    carry2 = s2 >> 21;
-    s3 += carry2;
-    s2 -= carry2 << 21;
-    carry3 = s3 >> 21;
-    s4 += carry3;
-    s3 -= carry3 << 21;
-    carry4 = s4 >> 21;
-    s5 += carry4;
-    s4 -= carry4 << 21;
-    carry5 = s5 >> 21;
-    s6 += carry5;
-    s5 -= carry5 << 21;
-    carry6 = s6 >> 21;
-    s7 += carry6;
-    s6 -= carry6 << 21;
-    carry7 = s7 >> 21;
-    s8 += carry7;
-    s7 -= carry7 << 21;
-    carry8 = s8 >> 21;
-    s9 += carry8;
-    s8 -= carry8 << 21;
-    carry9 = s9 >> 21;
-    s10 += carry9;
-    s9 -= carry9 << 21;
-    carry10 = s10 >> 21;
-    s11 += carry10;
-    s10 -= carry10 << 21;
-
-    s[0] = s0 as u8;
-    s[1] = (s0 >> 8) as u8;
-    s[2] = ((s0 >> 16) | (s1 << 5)) as u8;
-    s[3] = (s1 >> 3) as u8;
-    s[4] = (s1 >> 11) as u8;
-    s[5] = ((s1 >> 19) | (s2 << 2)) as u8;
-    s[6] = (s2 >> 6) as u8;
-    s[7] = ((s2 >> 14) | (s3 << 7)) as u8;
-    s[8] = (s3 >> 1) as u8;
-    s[9] = (s3 >> 9) as u8;
-    s[10] = ((s3 >> 17) | (s4 << 4)) as u8;
-    s[11] = (s4 >> 4) as u8;
-    s[12] = (s4 >> 12) as u8;
-    s[13] = ((s4 >> 20) | (s5 << 1)) as u8;
-    s[14] = (s5 >> 7) as u8;
-    s[15] = ((s5 >> 15) | (s6 << 6)) as u8;
-    s[16] = (s6 >> 2) as u8;
-    s[17] = (s6 >> 10) as u8;
-    s[18] = ((s6 >> 18) | (s7 << 3)) as u8;
-    s[19] = (s7 >> 5) as u8;
-    s[20] = (s7 >> 13) as u8;
-    s[21] = s8 as u8;
-    s[22] = (s8 >> 8) as u8;
-    s[23] = ((s8 >> 16) | (s9 << 5)) as u8;
-    s[24] = (s9 >> 3) as u8;
-    s[25] = (s9 >> 11) as u8;
-    s[26] = ((s9 >> 19) | (s10 << 2)) as u8;
-    s[27] = (s10 >> 6) as u8;
-    s[28] = ((s10 >> 14) | (s11 << 7)) as u8;
-    s[29] = (s11 >> 1) as u8;
-    s[30] = (s11 >> 9) as u8;
-    s[31] = (s11 >> 17) as u8;
+/// One step of the X25519 Montgomery ladder, the differential
+/// add-and-double of RFC 7748.
+///
+/// Takes the u-coordinate `x1` of `P3 - P2` and the projective points
+/// `P2 = (x2 : z2)` and `P3 = (x3 : z3)`, and returns `(2 P2, P2 + P3)`,
+/// again as `(x, z)` pairs. [`curve25519`] runs 255 of these with
+/// constant-time swaps between them.
+///
+/// Runs in constant time.
+pub fn montgomery_step(
+    x1: &FieldElement,
+    x2: &FieldElement,
+    z2: &FieldElement,
+    x3: &FieldElement,
+    z3: &FieldElement,
+) -> ((FieldElement, FieldElement), (FieldElement, FieldElement)) {
+    let d = *x3 - *z3;
+    let b = *x2 - *z2;
+    let a = *x2 + *z2;
+    let c = *x3 + *z3;
+    let da = d * a;
+    let cb = c * b;
+    let bb = b.square();
+    let aa = a.square();
+    let t0 = da + cb;
+    let t1 = da - cb;
+    let x4 = aa * bb;
+    let e = aa - bb;
+    let t2 = t1.square();
+    let t3 = e.mul_121666();
+    let x5 = t0.square();
+    let t4 = bb + t3;
+    let z5 = *x1 * t2;
+    let z4 = e * t4;
+
+    ((x4, z4), (x5, z5))
 }
 
 /// Generate a 32-byte curve25519 key, given a 32-byte curve25519 secret key
@@ -2558,6 +634,18 @@ pub fn sc_muladd(s: &mut [u8], a: &[u8], b: &[u8], c: &[u8]) {
 /// If the public argument is the predefined basepoint value (9 followed by all
 /// zeros), then this function will calculate a curve25519 public key.
 ///
+/// This is the X25519 function of RFC 7748: the secret is clamped first
+/// (bits 0, 1, 2 and 255 cleared, bit 254 set), so raw random bytes are a
+/// valid secret and a key that is already clamped is used as is. Both
+/// sides of a key agreement therefore agree whether or not they clamped
+/// their keys themselves.
+///
+/// With the `zeroize` feature, the clamped scalar and the ladder's state
+/// are overwritten before returning. That narrows what a later look at
+/// the stack can find but doesn't rule it out: the compiler is free to
+/// spill copies of any value to places this code can't reach, and the
+/// temporaries of each ladder step are only overwritten by the next step.
+///
 /// # Example
 ///
 /// ```rust
@@ -2575,22 +663,104 @@ pub fn sc_muladd(s: &mut [u8], a: &[u8], b: &[u8], c: &[u8]) {
 /// let my_publickey = curve25519(my_secretkey, basepoint);
 /// ```
 pub fn curve25519(secret: [u8; 32], public: [u8; 32]) -> [u8; 32] {
-    let e = secret.as_ref();
+    let mut e = clamp_scalar(secret);
+    let x1 = FieldElement::from_bytes_masked(&public);
+    let (mut x2, mut z2) = montgomery_ladder(&e, &x1, 255);
+    let shared = (z2.invert() * x2).to_bytes();
+
+    zeroize_hardened(&mut e);
+    zeroize_hardened(slice::from_mut(&mut x2));
+    zeroize_hardened(slice::from_mut(&mut z2));
+    shared
+}
+
+/// [`curve25519`] of one secret against many public keys:
+/// `out[i] = curve25519(secret, publics[i])`.
+///
+/// The secret is clamped once and the final inversions of the ladders are
+/// batched with [`FieldElement::batch_invert`], one per 64 keys instead of
+/// one per key. Runs in constant time; needs no allocation.
+///
+/// # Panics
+///
+/// Panics if `publics` and `out` have different lengths.
+pub fn x25519_many(
+    secret: [u8; 32],
+    publics: &[[u8; 32]],
+    out: &mut [[u8; 32]],
+) {
+    assert_eq!(publics.len(), out.len());
+
+    let mut e = clamp_scalar(secret);
+    for (publics, out) in publics
+        .chunks(BATCH_INVERT_CHUNK)
+        .zip(out.chunks_mut(BATCH_INVERT_CHUNK))
+    {
+        let mut xs = [FE_ZERO; BATCH_INVERT_CHUNK];
+        let mut zs = [FE_ONE; BATCH_INVERT_CHUNK];
+        for ((x, z), public) in xs.iter_mut().zip(zs.iter_mut()).zip(publics) {
+            let x1 = FieldElement::from_bytes_masked(public);
+            let (x2, z2) = montgomery_ladder(&e, &x1, 255);
+            *x = x2;
+            *z = z2;
+        }
+        FieldElement::batch_invert(&mut zs[..publics.len()]);
+
+        for ((bytes, x), z) in out.iter_mut().zip(&xs).zip(&zs) {
+            *bytes = (*z * *x).to_bytes();
+        }
+        zeroize_hardened(&mut xs);
+        zeroize_hardened(&mut zs);
+    }
+    zeroize_hardened(&mut e);
+}
+
+/// The Montgomery ladder of [`curve25519`] without any of the RFC 7748
+/// input handling: `scalar` isn't clamped, all 256 bits of it are used, and
+/// the top bit of `u` isn't masked, so `u` is read as a 256-bit integer and
+/// reduced modulo p.
+///
+/// This is NOT X25519 and isn't RFC 7748 compliant. It exists for
+/// differential testing against reference implementations and for
+/// experimenting with low-order inputs; use [`curve25519`] for key
+/// agreement.
+pub fn x25519_unclamped(scalar: [u8; 32], u: [u8; 32]) -> [u8; 32] {
+    let mut wide = [0u8; 64];
+    wide[..32].copy_from_slice(&u);
+    let x1 = FieldElement::from_bytes_wide_reduce(&wide);
+    let (mut x2, mut z2) = montgomery_ladder(&scalar, &x1, 256);
+    let shared = (z2.invert() * x2).to_bytes();
+
+    zeroize_hardened(slice::from_mut(&mut x2));
+    zeroize_hardened(slice::from_mut(&mut z2));
+    shared
+}
+
+// e * x1 for the low `bits` bits of e, as a projective u-coordinate
+// (X : Z). Z = 0 is the point at infinity.
+fn montgomery_ladder(
+    e: &[u8; 32],
+    x1: &FieldElement,
+    bits: usize,
+) -> (FieldElement, FieldElement) {
     let mut x2;
     let mut z2;
     let mut x3;
     let mut z3;
     let mut swap: i32;
     let mut b: i32;
-    let x1 = FieldElement::from_bytes(public.as_ref());
     x2 = FE_ONE;
     z2 = FE_ZERO;
-    x3 = x1;
+    x3 = *x1;
     z3 = FE_ONE;
 
+    // swap and b are always 0 or 1: b is a single bit of the scalar and
+    // swap is a xor of two of them, as maybe_swap_with requires. Both
+    // branches of every swap do the same work.
     swap = 0;
-    // pos starts at 254 and goes down to 0
-    for pos in (0usize..255).rev() {
+    b = 0;
+    // pos starts at bits - 1 and goes down to 0
+    for pos in (0usize..bits).rev() {
         b = i32::from(e[pos / 8] >> (pos & 7));
         b &= 1;
         swap ^= b;
@@ -2598,34 +768,21 @@ pub fn curve25519(secret: [u8; 32], public: [u8; 32]) -> [u8; 32] {
         z2.maybe_swap_with(&mut z3, swap);
         swap = b;
 
-        let d = x3 - z3;
-        let b = x2 - z2;
-        let a = x2 + z2;
-        let c = x3 + z3;
-        let da = d * a;
-        let cb = c * b;
-        let bb = b.square();
-        let aa = a.square();
-        let t0 = da + cb;
-        let t1 = da - cb;
-        let x4 = aa * bb;
-        let e = aa - bb;
-        let t2 = t1.square();
-        let t3 = e.mul_121666();
-        let x5 = t0.square();
-        let t4 = bb + t3;
-        let z5 = x1 * t2;
-        let z4 = e * t4;
-
-        z2 = z4;
-        z3 = z5;
+        let ((x4, z4), (x5, z5)) = montgomery_step(x1, &x2, &z2, &x3, &z3);
         x2 = x4;
+        z2 = z4;
         x3 = x5;
+        z3 = z5;
     }
     x2.maybe_swap_with(&mut x3, swap);
     z2.maybe_swap_with(&mut z3, swap);
 
-    (z2.invert() * x2).to_bytes()
+    // The caller clears x2 and z2 once it's done with them.
+    zeroize_hardened(slice::from_mut(&mut x3));
+    zeroize_hardened(slice::from_mut(&mut z3));
+    zeroize_hardened(slice::from_mut(&mut swap));
+    zeroize_hardened(slice::from_mut(&mut b));
+    (x2, z2)
 }
 
 /// Generate a 32-byte curve25519 secret key.
@@ -2634,15 +791,20 @@ pub fn curve25519(secret: [u8; 32], public: [u8; 32]) -> [u8; 32] {
 /// If you don't (i.e. use None for the `rand` arg), then a random 32-byte
 /// number will be generated with the best OS random number generator available.
 ///
+/// The OS random number generator is rand_core's `OsRng`, provided by the
+/// `getrandom` feature (enabled by `std`), and is drawn from with
+/// [`curve25519_sk_with_rng`]. Without the feature, `None` returns
+/// [`Error::Rng`] and you should use [`curve25519_sk_from_rng`] with your
+/// own entropy source instead.
+///
 /// # Example
 ///
 /// ```rust
-/// # use self::curve25519::curve25519_sk;
-/// # use rand::Error as RndError;
-/// # #[cfg(not(feature = "std"))]
+/// # use self::curve25519::{curve25519_sk, Error};
+/// # #[cfg(not(feature = "getrandom"))]
 /// # fn main() { }
-/// # #[cfg(feature = "std")]
-/// # fn main() -> Result<(), RndError> {
+/// # #[cfg(feature = "getrandom")]
+/// # fn main() -> Result<(), Error> {
 /// // Let curve25519_sk generate the random 32-byte value.
 /// let sk1 = curve25519_sk(None)?;
 ///
@@ -2653,35 +815,115 @@ pub fn curve25519(secret: [u8; 32], public: [u8; 32]) -> [u8; 32] {
 /// # Ok(())
 /// # }
 /// ```
-pub fn curve25519_sk(rand: Option<[u8; 32]>) -> Result<[u8; 32], RndError> {
-    // Fill a 32-byte buffer with random values if necessary.
-    // Otherwise, use the given 32-byte value.
-    let mut rand: [u8; 32] = match rand {
-        Some(r) => r,
+pub fn curve25519_sk(rand: Option<[u8; 32]>) -> Result<[u8; 32], Error> {
+    match rand {
+        Some(r) => Ok(clamp_scalar(r)),
 
-        #[cfg(feature = "std")]
+        // Draw from the OS random number generator.
+        #[cfg(feature = "getrandom")]
         None => {
-            let mut rng = OsRng::new()?;
-            let mut buf: [u8; 32] = [0; 32];
-            rng.fill(&mut buf);
-            buf
+            let mut rng = CheckedOsRng { failed: false };
+            let sk = curve25519_sk_with_rng(&mut rng);
+            if rng.failed {
+                return Err(Error::Rng);
+            }
+            Ok(sk)
         },
 
-        #[cfg(not(feature = "std"))]
-        None => {
-            return Err(RndError::new(
-                Unavailable,
-                "Cannot generate random without Standard Library",
-            ));
-        },
-    };
+        // There is no OS random number generator without `getrandom`.
+        #[cfg(not(feature = "getrandom"))]
+        None => Err(Error::Rng),
+    }
+}
+
+// OsRng, except that a failing fill_bytes is recorded instead of
+// panicking, so curve25519_sk can still return Error::Rng.
+#[cfg(feature = "getrandom")]
+struct CheckedOsRng {
+    failed: bool,
+}
+
+#[cfg(feature = "getrandom")]
+impl RngCore for CheckedOsRng {
+    fn next_u32(&mut self) -> u32 { rand_core::impls::next_u32_via_fill(self) }
+
+    fn next_u64(&mut self) -> u64 { rand_core::impls::next_u64_via_fill(self) }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        if self.try_fill_bytes(dest).is_err() {
+            self.failed = true;
+        }
+    }
+
+    fn try_fill_bytes(
+        &mut self,
+        dest: &mut [u8],
+    ) -> Result<(), rand_core::Error> {
+        rand_core::OsRng.try_fill_bytes(dest)
+    }
+}
+
+#[cfg(feature = "getrandom")]
+impl CryptoRng for CheckedOsRng {}
+
+// curve25519 secret key bit manip.
+#[inline]
+fn clamp_scalar(mut scalar: [u8; 32]) -> [u8; 32] {
+    scalar[0] &= 248;
+    scalar[31] &= 127;
+    scalar[31] |= 64;
+    scalar
+}
 
-    // curve25519 secret key bit manip.
-    rand[0] &= 248;
-    rand[31] &= 127;
-    rand[31] |= 64;
+/// Generate a 32-byte curve25519 secret key from the given random number
+/// generator.
+///
+/// This is the dependency-free path for `no_std` users who supply their own
+/// source of entropy instead of relying on the `getrandom` feature.
+///
+/// # Example
+///
+/// ```rust
+/// # use self::curve25519::{curve25519_sk_from_rng, Error};
+/// # use rand_core::{Error as RndError, RngCore};
+/// # struct HardwareRng;
+/// # impl RngCore for HardwareRng {
+/// #     fn next_u32(&mut self) -> u32 { 4 }
+/// #     fn next_u64(&mut self) -> u64 { 4 }
+/// #     fn fill_bytes(&mut self, dest: &mut [u8]) {
+/// #         dest.iter_mut().for_each(|b| *b = 4);
+/// #     }
+/// #     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), RndError> {
+/// #         self.fill_bytes(dest);
+/// #         Ok(())
+/// #     }
+/// # }
+/// # fn main() -> Result<(), Error> {
+/// let mut rng = HardwareRng; // Any entropy source implementing `RngCore`.
+///
+/// let sk = curve25519_sk_from_rng(&mut rng)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn curve25519_sk_from_rng(
+    rng: &mut impl RngCore,
+) -> Result<[u8; 32], Error> {
+    let mut buf: [u8; 32] = [0; 32];
+    rng.try_fill_bytes(&mut buf).map_err(|_| Error::Rng)?;
+    curve25519_sk(Some(buf))
+}
 
-    Ok(rand)
+/// Generate a 32-byte curve25519 secret key from a cryptographically secure
+/// random number generator.
+///
+/// Unlike [`curve25519_sk_from_rng`] this asks for a `CryptoRng`, and uses
+/// `RngCore::fill_bytes`, so a failing generator panics instead of returning
+/// an error. Seeding a deterministic CSPRNG makes key generation
+/// reproducible in tests.
+pub fn curve25519_sk_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> [u8; 32] {
+    let mut buf: [u8; 32] = [0; 32];
+    rng.fill_bytes(&mut buf);
+    clamp_scalar(buf)
 }
 
 /// Generate a 32-byte curve25519 public key.
@@ -2705,9 +947,62 @@ pub fn curve25519_pk(secret_key: [u8; 32]) -> [u8; 32] {
     curve25519(secret_key, basepoint)
 }
 
+/// Generate a 32-byte curve25519 public key with the fixed-base Edwards
+/// tables instead of the Montgomery ladder.
+///
+/// Clamps `secret`, multiplies the Ed25519 base point by it and maps the
+/// result to its Montgomery u-coordinate. The output is the X25519 public
+/// key, the same as `curve25519_pk` of the clamped secret, in a fraction of
+/// the time. Runs in constant time.
+pub fn x25519_base(secret: [u8; 32]) -> [u8; 32] {
+    ge_scalarmult_base(&clamp_scalar(secret))
+        .to_montgomery()
+        .to_bytes()
+}
+
+/// Maps an X25519 public key to the encoding of the Ed25519 public key it
+/// came from, the inverse of the map `x25519_base` ends with.
+///
+/// Computes `y = (u - 1) / (u + 1)`. The map from Edwards to Montgomery
+/// forgets the sign of x, so `sign_bit` supplies it; it becomes the top bit
+/// of the output. Returns `None` for `u = -1`, which has no image. The top
+/// bit of `u` is ignored, as in X25519.
+///
+/// The output is only an encoding: for a `u` on the twist rather than the
+/// curve there's no matching x and decoding it fails.
+pub fn x25519_pk_to_edwards(u: &[u8; 32], sign_bit: bool) -> Option<[u8; 32]> {
+    let u = FieldElement::from_bytes(u);
+    let u_plus_one = u + FE_ONE;
+    if !u_plus_one.is_nonzero() {
+        return None;
+    }
+    let y = (u - FE_ONE) * u_plus_one.invert();
+    let mut bytes = y.to_bytes();
+    bytes[31] |= u8::from(sign_bit) << 7;
+    Some(bytes)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{curve25519_pk, curve25519_sk, FieldElement};
+    extern crate std;
+
+    use super::{
+        clamp_scalar, compress_batch, curve25519, curve25519_pk, curve25519_sk,
+        curve25519_sk_from_rng, curve25519_sk_with_rng,
+        ed25519_scalarmult_base_compressed,
+        hazmat::{
+            commit, ge_cached_select, ge_precomp_select, ge_scalarmult_base,
+            ge_scalarmult_ct, sc_muladd, sc_reduce, GeP2, GeP3, GePrecomp,
+        },
+        montgomery_step, multiscalar_mul, scalar_muladd, scalar_reduce,
+        statics::GE_PRECOMP_BASE,
+        util::from_hex,
+        x25519_base, x25519_many, x25519_pk_to_edwards, x25519_unclamped,
+        Error, FieldElement, Scalar, FIELD_MODULUS, GROUP_ORDER,
+    };
+    use core::num::NonZeroU32;
+    use rand_core::{impls, CryptoRng, Error as RndError, RngCore};
+    use std::format;
 
     struct CurveGen {
         which: u32,
@@ -2722,15 +1017,32 @@ mod tests {
 
         fn next(&mut self) -> Option<FieldElement> {
             let mut e: [u8; 32] = [0; 32];
-            // .map(|idx| (idx * (1289 + self.which * 761)) as u8)
-            // .collect();
-            for idx in e.iter_mut() {
-                *idx *= (1289 + self.which * 761) as u8;
+            let step = self.which.wrapping_mul(761).wrapping_add(1289);
+            for (pos, idx) in e.iter_mut().enumerate() {
+                *idx = (pos as u32).wrapping_mul(step) as u8;
             }
             e[0] &= 248;
             e[31] &= 127;
             e[31] |= 64;
-            Some(FieldElement::from_bytes(e.as_ref()))
+            // Step through the seeds with an LCG so every call yields a
+            // different element.
+            self.which =
+                self.which.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            Some(FieldElement::from_bytes(&e))
+        }
+    }
+
+    #[test]
+    fn curve_gen_is_varied() {
+        let xs: [FieldElement; 8] = {
+            let mut gen = CurveGen::new(1);
+            [(); 8].map(|_| gen.next().unwrap())
+        };
+        for (i, x) in xs.iter().enumerate() {
+            assert!(x.to_bytes()[1..31].iter().any(|&b| b != 0));
+            for y in &xs[i + 1..] {
+                assert!(x != y);
+            }
         }
     }
 
@@ -2738,15 +1050,15 @@ mod tests {
     fn from_to_bytes_preserves() {
         for i in 0..50 {
             let mut e: [u8; 32] = [0; 32];
-            // .map(|idx| (idx * (1289 + i * 761)) as u8)
-            // .collect();
-            for idx in e.iter_mut() {
-                *idx *= (1289 + i * 761) as u8;
+            let step = (i as u32).wrapping_mul(761).wrapping_add(1289);
+            for (pos, idx) in e.iter_mut().enumerate() {
+                *idx = (pos as u32).wrapping_mul(step) as u8;
             }
             e[0] &= 248;
             e[31] &= 127;
             e[31] |= 64;
-            let fe = FieldElement::from_bytes(e.as_ref());
+            assert!(e[1..31].iter().any(|&b| b != 0));
+            let fe = FieldElement::from_bytes(&e);
             let e_preserved = fe.to_bytes();
             assert!(e == e_preserved);
         }
@@ -2754,8 +1066,10 @@ mod tests {
 
     #[test]
     fn swap_test() {
-        let mut f = FieldElement([10, 20, 30, 40, 50, 60, 70, 80, 90, 100]);
-        let mut g = FieldElement([11, 21, 31, 41, 51, 61, 71, 81, 91, 101]);
+        let mut f =
+            FieldElement::from_limbs([10, 20, 30, 40, 50, 60, 70, 80, 90, 100]);
+        let mut g =
+            FieldElement::from_limbs([11, 21, 31, 41, 51, 61, 71, 81, 91, 101]);
         let f_initial = f;
         let g_initial = g;
         f.maybe_swap_with(&mut g, 0);
@@ -2768,26 +1082,752 @@ mod tests {
     }
 
     #[test]
-    fn mul_assoc() {
-        for (x, (y, z)) in CurveGen::new(1)
-            .zip(CurveGen::new(2).zip(CurveGen::new(3)))
-            .take(40)
-        {
-            assert!((x * y) * z == x * (y * z));
-        }
+    fn swap_and_set_negative_limbs() {
+        let f_initial = FieldElement::from_limbs([
+            -1,
+            20,
+            -33_554_432,
+            40,
+            -50,
+            16_777_215,
+            -70,
+            80,
+            -90,
+            -1,
+        ]);
+        let g_initial = FieldElement::from_limbs([
+            11,
+            -21,
+            31,
+            -16_777_216,
+            51,
+            -61,
+            33_554_431,
+            -81,
+            91,
+            -101,
+        ]);
+
+        let (mut f, mut g) = (f_initial, g_initial);
+        f.maybe_swap_with(&mut g, 0);
+        assert!(f == f_initial && g == g_initial);
+        f.maybe_swap_with(&mut g, 1);
+        assert!(f == g_initial && g == f_initial);
+
+        let mut h = f_initial;
+        h.maybe_set(&g_initial, 0);
+        assert!(h == f_initial);
+        h.maybe_set(&g_initial, 1);
+        assert!(h == g_initial);
     }
 
     #[test]
-    fn invert_inverts() {
-        for x in CurveGen::new(1).take(40) {
-            assert!(x.invert().invert() == x);
+    fn scalarmult_matches_vartime() {
+        let p = ge_scalarmult_base(&test_scalar(11));
+        for seed in 0..8 {
+            let k = test_scalar(seed);
+            assert!(ge_scalarmult_ct(&k, &p) == multiscalar_mul(&[k], &[p]));
         }
+        assert!(ge_scalarmult_ct(&[0; 32], &p) == GeP3::zero());
     }
 
     #[test]
-    fn square_by_mul() {
-        for x in CurveGen::new(1).take(40) {
-            assert!(x * x == x.square());
+    fn scalarmult_ct_matches_double_and_add() {
+        let p = ge_scalarmult_base(&test_scalar(5));
+        let mut largest = [0xff; 32];
+        largest[31] = 0x7f;
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        for k in [test_scalar(1), test_scalar(2), largest, one].iter() {
+            let mut expected = GeP3::zero();
+            for i in (0..256).rev() {
+                expected = expected.double_repeatedly(1);
+                if (k[i / 8] >> (i % 8)) & 1 == 1 {
+                    expected = expected + p;
+                }
+            }
+            assert!(ge_scalarmult_ct(k, &p) == expected);
+        }
+    }
+
+    #[test]
+    fn commit_is_additive() {
+        let h = ge_scalarmult_base(&test_scalar(7));
+        let a1 = Scalar::from_bytes_mod_order(test_scalar(1));
+        let b1 = Scalar::from_bytes_mod_order(test_scalar(2));
+        let a2 = Scalar::from_bytes_mod_order(test_scalar(3));
+        let b2 = Scalar::from_bytes_mod_order(test_scalar(4));
+
+        let sum = commit(a1.as_bytes(), b1.as_bytes(), &h)
+            + commit(a2.as_bytes(), b2.as_bytes(), &h);
+        let expected = commit((a1 + a2).as_bytes(), (b1 + b2).as_bytes(), &h);
+        assert!(sum == expected);
+
+        let zero = [0u8; 32];
+        assert!(
+            commit(a1.as_bytes(), &zero, &h)
+                == ge_scalarmult_base(a1.as_bytes())
+        );
+        assert!(
+            commit(&zero, b1.as_bytes(), &h)
+                == ge_scalarmult_ct(b1.as_bytes(), &h)
+        );
+    }
+
+    #[test]
+    fn point_eq_matches_bytes() {
+        let points: [GeP3; 4] = [
+            GeP3::zero(),
+            small_multiple(1),
+            small_multiple(2),
+            ge_scalarmult_base(&test_scalar(9)),
+        ];
+        for p in points.iter() {
+            for q in points.iter() {
+                assert_eq!(p == q, p.to_bytes() == q.to_bytes());
+            }
+        }
+    }
+
+    #[test]
+    fn scalarmult_base_compressed() {
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        // The base point: y = 4/5, x positive.
+        let mut b = [0x66u8; 32];
+        b[0] = 0x58;
+        assert_eq!(ed25519_scalarmult_base_compressed(&one), b);
+
+        let k = test_scalar(6);
+        assert_eq!(
+            ed25519_scalarmult_base_compressed(&k),
+            ge_scalarmult_base(&k).to_bytes()
+        );
+    }
+
+    // Scalars that make slide carry as far as it can.
+    fn slide_edge_scalars() -> [[u8; 32]; 5] {
+        let mut ones = [0xff; 32];
+        ones[31] = 0x7f; // 2^255 - 1
+        let mut p = ones;
+        p[0] = 0xed; // 2^255 - 19
+        let mut top = [0; 32];
+        top[31] = 0x40; // 2^254
+        let mut alternating = [0xaa; 32];
+        alternating[31] = 0x7a;
+        let mut high_run = [0u8; 32];
+        high_run[30] = 0xf8;
+        high_run[31] = 0x7f;
+        [ones, p, top, alternating, high_run]
+    }
+
+    #[test]
+    fn slide_digits_sum_to_scalar() {
+        use num_bigint::{BigInt, Sign};
+
+        for a in slide_edge_scalars().iter().chain(&[test_scalar(5)]) {
+            let digits = GeP2::slide(a);
+            let mut sum = BigInt::from(0);
+            for (i, &d) in digits.iter().enumerate() {
+                assert!(d == 0 || (d % 2 != 0 && (-15..=15).contains(&d)));
+                sum += BigInt::from(d) << i;
+            }
+            assert_eq!(sum, BigInt::from_bytes_le(Sign::Plus, a));
+        }
+    }
+
+    #[test]
+    fn double_scalarmult_vartime_edge_scalars() {
+        let a_point = small_multiple(3);
+        let scalars = slide_edge_scalars();
+        for a in scalars.iter() {
+            for b in scalars.iter() {
+                let expected = (ge_scalarmult_ct(a, &a_point)
+                    + ge_scalarmult_base(b).to_cached())
+                .to_p3();
+                let r = GeP2::double_scalarmult_vartime(a, a_point, b);
+                assert_eq!(r.to_bytes(), expected.to_bytes());
+            }
+        }
+    }
+
+    #[test]
+    fn double_scalarmult_vartime_with_table() {
+        let a_point = small_multiple(3);
+        let base_table = small_multiple(1).odd_multiples();
+        let generator = ge_scalarmult_base(&test_scalar(11));
+        let table = generator.odd_multiples();
+        let scalars = slide_edge_scalars();
+        for a in scalars.iter() {
+            for b in scalars.iter() {
+                let with_base = GeP2::double_scalarmult_vartime_with(
+                    a,
+                    a_point,
+                    b,
+                    &base_table,
+                );
+                let r = GeP2::double_scalarmult_vartime(a, a_point, b);
+                assert_eq!(with_base.to_bytes(), r.to_bytes());
+
+                let expected = ge_scalarmult_ct(a, &a_point)
+                    + ge_scalarmult_ct(b, &generator);
+                let r =
+                    GeP2::double_scalarmult_vartime_with(a, a_point, b, &table);
+                assert_eq!(r.to_bytes(), expected.to_bytes());
+            }
+        }
+    }
+
+    #[test]
+    fn double_scalarmult_base_subtracts() {
+        let a = ge_scalarmult_base(&test_scalar(1));
+        let s = test_scalar(2);
+        let c = test_scalar(3);
+        let expected = (ge_scalarmult_base(&s)
+            + multiscalar_mul(&[c], &[a]).neg().to_cached())
+        .to_p3();
+        assert_eq!(
+            GeP3::vartime_double_scalarmult_base(&s, &a, &c).to_bytes(),
+            expected.to_bytes()
+        );
+    }
+
+    #[test]
+    fn point_add_sub() {
+        let p = ge_scalarmult_base(&test_scalar(1));
+        let q = ge_scalarmult_base(&test_scalar(2));
+        assert_eq!(((p + q) - q).to_bytes(), p.to_bytes());
+        assert_eq!((p + q).to_bytes(), (q + p).to_bytes());
+        assert!((p - p).is_identity());
+        assert!((p - q) == p + q.neg());
+    }
+
+    #[test]
+    fn double_repeatedly_multiplies_by_power_of_two() {
+        let p = ge_scalarmult_base(&test_scalar(6));
+        assert!(p.double_repeatedly(0) == p);
+        assert!(p.double_repeatedly(1) == p + p);
+
+        let cofactor = p.dbl().to_p3().dbl().to_p3().dbl().to_p3();
+        assert!(p.double_repeatedly(3) == cofactor);
+
+        let mut k = [0u8; 32];
+        k[1] = 1; // 2^8
+        assert!(p.double_repeatedly(8) == ge_scalarmult_ct(&k, &p));
+    }
+
+    #[test]
+    fn compress_batch_matches_to_bytes() {
+        // More than one chunk, and points with Z != 1.
+        let mut points = [GeP3::zero(); 70];
+        let mut p = ge_scalarmult_base(&test_scalar(9));
+        for point in points.iter_mut().skip(1) {
+            *point = p;
+            p = p + p.double_repeatedly(1);
+        }
+
+        let mut out = [[0u8; 32]; 70];
+        compress_batch(&points, &mut out);
+        for (point, bytes) in points.iter().zip(out.iter()) {
+            assert_eq!(point.to_bytes(), *bytes);
+        }
+
+        compress_batch(&[], &mut []);
+    }
+
+    #[test]
+    fn point_conversions() {
+        let p = ge_scalarmult_base(&test_scalar(3));
+        let q = ge_scalarmult_base(&test_scalar(4));
+        let sum = p + q.to_cached();
+
+        assert_eq!(GeP2::from(p).to_bytes(), p.to_bytes());
+        assert!(GeP3::from(sum) == p + q);
+        assert_eq!(GeP2::from(sum).to_bytes(), (p + q).to_bytes());
+    }
+
+    #[test]
+    fn add_cached_and_precomp() {
+        let p = ge_scalarmult_base(&test_scalar(3));
+        let q = ge_scalarmult_base(&test_scalar(4));
+        let q_cached = q.to_cached();
+        let q_precomp = GePrecomp::from_p3(&q);
+
+        assert!(p.add_cached(&q_cached) == (p + q_cached).to_p3());
+        assert!(p.add_precomp(&q_precomp) == (p + q_precomp).to_p3());
+        assert!(p.add_cached(&q_cached) == p + q);
+        assert!(p.add_precomp(&q_precomp) == p + q);
+    }
+
+    #[test]
+    fn neg_operator() {
+        for x in CurveGen::new(3).take(10) {
+            assert!(-x + x == FieldElement::zero());
+            assert!(-(-x) == x);
+            assert!(-&x == x.neg());
+        }
+        assert!(-FieldElement::zero() == FieldElement::zero());
+    }
+
+    #[test]
+    fn conditional_assign_and_swap() {
+        let a = FieldElement::from_u64(3);
+        let b = FieldElement::from_u64(5);
+        for &choice in [0u8, 1].iter() {
+            let mut assigned = a;
+            assigned.conditional_assign(&b, choice);
+            let mut expected = a;
+            expected.maybe_set(&b, i32::from(choice));
+            assert!(assigned == expected);
+            assert!(assigned == if choice == 1 { b } else { a });
+
+            let (mut x, mut y) = (a, b);
+            x.conditional_swap(&mut y, choice);
+            let (mut ex, mut ey) = (a, b);
+            ex.maybe_swap_with(&mut ey, i32::from(choice));
+            assert!(x == ex && y == ey);
+            assert!(x == if choice == 1 { b } else { a });
+            assert!(y == if choice == 1 { a } else { b });
+        }
+    }
+
+    #[test]
+    fn default_is_zero() {
+        assert!(FieldElement::default() == FieldElement::zero());
+        let zeros = [FieldElement::default(); 4];
+        assert!(zeros.iter().all(|&z| z.to_bytes() == [0; 32]));
+    }
+
+    #[test]
+    fn encode_into_matches_to_bytes() {
+        let p = ge_scalarmult_base(&test_scalar(3));
+        let mut frame = [0xaa; 100];
+
+        p.y.encode_into(&mut frame[1..33]);
+        p.encode_into(&mut frame[33..65]);
+        GeP2::from(p).encode_into(&mut frame[65..97]);
+        assert_eq!(frame[1..33], p.y.to_bytes());
+        assert_eq!(frame[33..65], p.to_bytes());
+        assert_eq!(frame[65..97], p.to_bytes());
+        assert_eq!(frame[0], 0xaa);
+        assert_eq!(frame[97..], [0xaa; 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "encode_into: out must be 32 bytes")]
+    fn encode_into_rejects_short_buffer() {
+        GeP3::zero().encode_into(&mut [0; 31]);
+    }
+
+    #[test]
+    fn to_bytes_normalized() {
+        for seed in 0..4 {
+            let p = ge_scalarmult_base(&test_scalar(seed)) + GeP3::zero();
+            let (bytes, normalized) = p.to_bytes_normalized();
+            assert_eq!(bytes, p.to_bytes());
+            assert!(normalized == p);
+            assert!(normalized.z == FieldElement::one());
+            assert!(normalized.t == normalized.x * normalized.y);
+            assert_eq!(
+                GeP3::from_bytes_vartime(&bytes).unwrap().to_bytes(),
+                normalized.to_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn to_affine_matches_to_bytes() {
+        let p = ge_scalarmult_base(&test_scalar(7));
+        let p2 =
+            GeP3::vartime_double_scalarmult_base(&test_scalar(7), &p, &[0; 32]);
+        for &((x, y), bytes) in [
+            (p.to_affine(), p.to_bytes()),
+            (p2.to_affine(), p2.to_bytes()),
+        ]
+        .iter()
+        {
+            let mut recompressed = y.to_bytes();
+            recompressed[31] |= u8::from(x.is_negative()) << 7;
+            assert_eq!(recompressed, bytes);
+        }
+
+        let (x, y) = GeP3::zero().to_affine();
+        assert!(x == FieldElement::zero() && y == FieldElement::one());
+    }
+
+    #[test]
+    fn neg_cancels() {
+        assert!(GeP3::zero().is_identity());
+        assert!(GeP3::zero().neg().is_identity());
+        for seed in 0..4 {
+            let p = ge_scalarmult_base(&test_scalar(seed));
+            assert!(!p.is_identity());
+            assert!((p + p.neg().to_cached()).to_p3().is_identity());
+            assert!(p.neg().neg() == p);
+        }
+    }
+
+    #[test]
+    fn point_debug_prints_encoding() {
+        let identity = ge_scalarmult_base(&[0; 32]);
+        let expected = format!("GeP3(01{})", "00".repeat(31));
+        assert_eq!(format!("{:?}", identity), expected);
+
+        // Scaled coordinates print the same encoding.
+        let p = ge_scalarmult_base(&test_scalar(3));
+        let lambda = FieldElement::from_bytes(&test_scalar(4));
+        let scaled = GeP3 {
+            x: p.x * lambda,
+            y: p.y * lambda,
+            z: p.z * lambda,
+            t: p.t * lambda,
+        };
+        assert_eq!(format!("{:?}", scaled), format!("{:?}", p));
+
+        let p2 = GeP3::vartime_double_scalarmult_base(
+            &test_scalar(3),
+            &identity,
+            &[0; 32],
+        );
+        assert_eq!(
+            format!("{:?}", p2),
+            format!("{:?}", p).replace("GeP3", "GeP2")
+        );
+
+        // Not a point, but printing it mustn't panic.
+        let zero = FieldElement::zero();
+        let invalid = GeP3 {
+            x: zero,
+            y: zero,
+            z: zero,
+            t: zero,
+        };
+        assert_eq!(
+            format!("{:?}", invalid),
+            format!("GeP3({})", "00".repeat(32))
+        );
+    }
+
+    #[test]
+    fn from_bytes_strict_rejects_non_canonical() {
+        for k in 1..8 {
+            let bytes = small_multiple(k).to_bytes();
+            assert!(
+                GeP3::from_bytes_strict(&bytes).unwrap() == small_multiple(k)
+            );
+        }
+
+        // y = p + 1, a non-canonical encoding of the identity.
+        let mut y_above_p = [0xff; 32];
+        y_above_p[0] = 0xee;
+        y_above_p[31] = 0x7f;
+        assert!(GeP3::from_bytes_vartime(&y_above_p).unwrap().is_identity());
+        assert!(GeP3::from_bytes_strict(&y_above_p).is_none());
+
+        // The identity again, x = 0 with the sign bit set.
+        let mut negative_zero = [0u8; 32];
+        negative_zero[0] = 1;
+        negative_zero[31] = 0x80;
+        assert!(GeP3::from_bytes_vartime(&negative_zero).is_some());
+        assert!(GeP3::from_bytes_strict(&negative_zero).is_none());
+    }
+
+    #[test]
+    fn point_eq_ignores_scaling() {
+        let p = ge_scalarmult_base(&test_scalar(3));
+        let lambda = FieldElement::from_bytes(&test_scalar(4));
+        let scaled = GeP3 {
+            x: p.x * lambda,
+            y: p.y * lambda,
+            z: p.z * lambda,
+            t: p.t * lambda,
+        };
+        assert!(scaled == p);
+        assert!(scaled != small_multiple(1));
+    }
+
+    #[test]
+    fn from_limbs_checked_accepts_bounded() {
+        let limbs = [73_819_750, -36_909_875, 0, 1, -1, 0, 0, 0, 0, 0];
+        assert!(
+            FieldElement::from_limbs_checked(limbs)
+                == FieldElement::from_limbs(limbs)
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn from_limbs_checked_rejects_huge_limbs() {
+        FieldElement::from_limbs_checked([0, 1 << 30, 0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn montgomery_step_from_infinity() {
+        // From P2 = O and P3 = P the step gives (2 O, O + P) = (O, P).
+        let x1 = FieldElement::from_u64(9);
+        let one = FieldElement::one();
+        let zero = FieldElement::zero();
+        let ((x4, z4), (x5, z5)) = montgomery_step(&x1, &one, &zero, &x1, &one);
+        assert!(!z4.is_nonzero() && x4.is_nonzero());
+        assert!(x5 * z5.invert() == x1);
+    }
+
+    #[test]
+    fn from_u64_matches_bytes() {
+        assert!(FieldElement::from_u64(0) == FieldElement::zero());
+        assert!(FieldElement::from_u64(1) == FieldElement::one());
+        for &n in [2, 486_662, 1 << 26, 1 << 51, u64::MAX].iter() {
+            let mut bytes = [0u8; 32];
+            bytes[..8].copy_from_slice(&n.to_le_bytes());
+            assert_eq!(FieldElement::from_u64(n).to_bytes(), bytes);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::op_ref)]
+    fn ref_ops_match_value_ops() {
+        let mut ys = CurveGen::new(9);
+        for x in CurveGen::new(8).take(10) {
+            let y = ys.next().unwrap();
+            assert!(&x + &y == x + y);
+            assert!(&x - &y == x - y);
+            assert!(&x * &y == x * y);
+        }
+    }
+
+    #[test]
+    fn conditional_negate_matches_neg() {
+        for x in CurveGen::new(7).take(10) {
+            let mut y = x;
+            y.conditional_negate(0);
+            assert!(y == x);
+            y.conditional_negate(1);
+            assert!(y == x.neg());
+            y.conditional_negate(1);
+            assert!(y == x);
+        }
+    }
+
+    #[test]
+    fn mul_small_matches_mul() {
+        for x in CurveGen::new(5).take(40) {
+            assert!(x.mul_small(2) == x + x);
+            assert!(x.mul_small(121_666) == x.mul_121666());
+
+            let k = FieldElement::from_u64(0xf_ffff);
+            assert!(x.mul_small(0xf_ffff) == x * k);
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn swap_rejects_wide_flag() {
+        let mut f = FieldElement::from_limbs([1; 10]);
+        let mut g = FieldElement::from_limbs([2; 10]);
+        f.maybe_swap_with(&mut g, 2);
+    }
+
+    // Differential tests against num-bigint: `from_bytes` drops the top
+    // bit, everything else is arithmetic modulo p = 2^255 - 19.
+    mod reference {
+        extern crate std;
+
+        use crate::FieldElement;
+        use num_bigint::BigUint;
+        use proptest::prelude::*;
+
+        fn p() -> BigUint { (BigUint::from(1u8) << 255) - 19u8 }
+
+        fn to_big(bytes: &[u8; 32]) -> BigUint {
+            let mut bytes = *bytes;
+            bytes[31] &= 127;
+            BigUint::from_bytes_le(&bytes) % p()
+        }
+
+        fn fe(bytes: &[u8; 32]) -> FieldElement {
+            FieldElement::from_bytes(bytes)
+        }
+
+        fn fe_to_big(x: FieldElement) -> BigUint {
+            BigUint::from_bytes_le(&x.to_bytes())
+        }
+
+        proptest! {
+            #[test]
+            fn add(a: [u8; 32], b: [u8; 32]) {
+                let expected = (to_big(&a) + to_big(&b)) % p();
+                prop_assert_eq!(fe_to_big(fe(&a) + fe(&b)), expected);
+            }
+
+            #[test]
+            fn sub(a: [u8; 32], b: [u8; 32]) {
+                let expected = (to_big(&a) + p() - to_big(&b)) % p();
+                prop_assert_eq!(fe_to_big(fe(&a) - fe(&b)), expected);
+            }
+
+            #[test]
+            fn mul(a: [u8; 32], b: [u8; 32]) {
+                let expected = to_big(&a) * to_big(&b) % p();
+                prop_assert_eq!(fe_to_big(fe(&a) * fe(&b)), expected);
+            }
+
+            #[test]
+            fn reduced_after_additions(a: [u8; 32], b: [u8; 32]) {
+                let mut sum = fe(&a);
+                for _ in 0..10 {
+                    sum = sum + fe(&a);
+                }
+                let expected = to_big(&a) * 11u8 * to_big(&b) % p();
+                prop_assert_eq!(fe_to_big(sum.reduced() * fe(&b)), expected);
+            }
+
+            #[test]
+            fn square(a: [u8; 32]) {
+                let expected = to_big(&a).pow(2) % p();
+                prop_assert_eq!(fe_to_big(fe(&a).square()), expected);
+            }
+
+            #[test]
+            fn from_bytes_wide_reduce(lo: [u8; 32], hi: [u8; 32]) {
+                let mut wide = [0u8; 64];
+                wide[..32].copy_from_slice(&lo);
+                wide[32..].copy_from_slice(&hi);
+                let expected = BigUint::from_bytes_le(&wide) % p();
+                let x = FieldElement::from_bytes_wide_reduce(&wide);
+                prop_assert_eq!(fe_to_big(x), expected);
+            }
+
+            #[test]
+            fn invert(a: [u8; 32]) {
+                let expected = to_big(&a).modpow(&(p() - 2u8), &p());
+                prop_assert_eq!(fe_to_big(fe(&a).invert()), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn modulus_constants() {
+        use num_bigint::BigUint;
+
+        let p = (BigUint::from(1u8) << 255) - BigUint::from(19u8);
+        let l = (BigUint::from(1u8) << 252)
+            + "27742317777372353535851937790883648493"
+                .parse::<BigUint>()
+                .unwrap();
+        assert_eq!(BigUint::from_bytes_le(&FIELD_MODULUS), p);
+        assert_eq!(BigUint::from_bytes_le(&GROUP_ORDER), l);
+
+        assert!(!FieldElement::is_canonical_bytes(&FIELD_MODULUS));
+        assert!(!Scalar::is_canonical(&GROUP_ORDER));
+    }
+
+    #[test]
+    fn is_canonical_bytes_boundaries() {
+        // p - 1, p and p + 1.
+        let mut p_minus_one = [0xff; 32];
+        p_minus_one[0] = 0xec;
+        p_minus_one[31] = 0x7f;
+        let mut p = p_minus_one;
+        p[0] = 0xed;
+        let mut p_plus_one = p_minus_one;
+        p_plus_one[0] = 0xee;
+
+        assert!(FieldElement::is_canonical_bytes(&[0; 32]));
+        assert!(FieldElement::is_canonical_bytes(&p_minus_one));
+        assert!(!FieldElement::is_canonical_bytes(&p));
+        assert!(!FieldElement::is_canonical_bytes(&p_plus_one));
+        assert!(!FieldElement::is_canonical_bytes(&[0xff; 32]));
+
+        // Zero and one with the top bit set.
+        let mut top_bit = [0u8; 32];
+        top_bit[31] = 0x80;
+        assert!(!FieldElement::is_canonical_bytes(&top_bit));
+        top_bit[0] = 1;
+        assert!(!FieldElement::is_canonical_bytes(&top_bit));
+    }
+
+    #[test]
+    fn from_bytes_wide_reduce_edges() {
+        // 2^512 - 1 = 38^2 - 1 = 1443 (mod p).
+        let x = FieldElement::from_bytes_wide_reduce(&[0xff; 64]);
+        assert!(x == FieldElement::from_u64(1443));
+
+        // p itself, with the high half empty, reduces to zero.
+        let mut wide = [0u8; 64];
+        wide[0] = 0xed;
+        wide[1..31].copy_from_slice(&[0xff; 30]);
+        wide[31] = 0x7f;
+        assert!(!FieldElement::from_bytes_wide_reduce(&wide).is_nonzero());
+    }
+
+    #[test]
+    fn mul_assoc() {
+        for (x, (y, z)) in CurveGen::new(1)
+            .zip(CurveGen::new(2).zip(CurveGen::new(3)))
+            .take(40)
+        {
+            assert!((x * y) * z == x * (y * z));
+        }
+    }
+
+    #[test]
+    fn invert_inverts() {
+        for x in CurveGen::new(1).take(40) {
+            assert!(x.invert().invert() == x);
+        }
+    }
+
+    #[test]
+    fn batch_invert_matches_invert() {
+        let mut xs = [FieldElement::zero(); 150];
+        for (x, y) in xs.iter_mut().zip(CurveGen::new(3)) {
+            *x = y;
+        }
+        xs[0] = FieldElement::zero();
+        xs[70] = FieldElement::zero();
+
+        let mut inverted = xs;
+        FieldElement::batch_invert(&mut inverted);
+        for (x, inv) in xs.iter().zip(inverted.iter()) {
+            assert!(*inv == x.invert());
+        }
+
+        FieldElement::batch_invert(&mut []);
+    }
+
+    #[test]
+    fn pow2k_repeats_square() {
+        for x in CurveGen::new(4).take(20) {
+            assert!(x.pow2k(0) == x);
+            assert!(x.pow2k(1) == x.square());
+            assert!(x.pow2k(3) == x.square().square().square());
+        }
+    }
+
+    #[test]
+    fn square_by_mul() {
+        for x in CurveGen::new(1).take(40) {
+            assert!(x * x == x.square());
+        }
+    }
+
+    #[test]
+    fn mul4_matches_mul() {
+        let mut xs = CurveGen::new(1);
+        let mut ys = CurveGen::new(2);
+        for _ in 0..10 {
+            let mut a = [FieldElement::from_bytes(&[0; 32]); 4];
+            let mut b = a;
+            for i in 0..4 {
+                a[i] = xs.next().unwrap();
+                b[i] = ys.next().unwrap();
+            }
+            let h = FieldElement::mul4(a, b);
+            for i in 0..4 {
+                assert!(h[i] == a[i] * b[i]);
+            }
         }
     }
 
@@ -2806,4 +1846,481 @@ mod tests {
         ];
         assert_eq!(pk.to_vec(), correct.to_vec());
     }
+
+    #[test]
+    fn mul_base_clamped_is_edwards_x25519_key() {
+        for seed in 0..8 {
+            let sk = test_scalar(seed);
+            let ed = GeP3::mul_base_clamped(sk);
+            let point = GeP3::from_bytes_vartime(&ed).unwrap();
+            assert_eq!(point.to_montgomery().to_bytes(), curve25519_pk(sk));
+        }
+    }
+
+    #[test]
+    fn x25519_base_matches_ladder() {
+        for seed in 0..16 {
+            let sk = test_scalar(seed);
+            assert_eq!(x25519_base(sk), curve25519_pk(sk));
+        }
+        // RFC 7748, section 6.1: Alice's key pair.
+        let sk = from_hex(
+            "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+        );
+        assert_eq!(
+            sk.map(x25519_base),
+            from_hex(
+                "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"
+            )
+        );
+    }
+
+    #[test]
+    fn x25519_many_matches_curve25519() {
+        let secret = [0x77; 32];
+        let mut publics = [[0u8; 32]; 70];
+        for (i, pk) in publics.iter_mut().enumerate() {
+            *pk = curve25519_pk(test_scalar(i as u8));
+        }
+        // Low-order inputs give zero, also in the middle of a batch.
+        publics[5] = [0; 32];
+        publics[6] = [0; 32];
+        publics[6][0] = 1;
+
+        let mut out = [[0xaa; 32]; 70];
+        x25519_many(secret, &publics, &mut out);
+        for (pk, shared) in publics.iter().zip(out.iter()) {
+            assert_eq!(*shared, curve25519(secret, *pk));
+        }
+        assert_eq!(out[5], [0; 32]);
+    }
+
+    #[test]
+    fn x25519_unclamped_is_the_raw_ladder() {
+        let mut u = [0u8; 32];
+        u[0] = 9;
+        let k = clamp_scalar([0x5a; 32]);
+        assert_eq!(x25519_unclamped(k, u), curve25519(k, u));
+
+        // The top bit of u counts: 9 + 2^255 = 28 mod p.
+        let mut u_top = u;
+        u_top[31] |= 0x80;
+        let mut u_28 = [0u8; 32];
+        u_28[0] = 28;
+        assert_eq!(x25519_unclamped(k, u_top), curve25519(k, u_28));
+
+        // u = 1 has order 4 and u = 0 order 2, neither reachable with a
+        // clamped scalar.
+        let small = |n: u8| {
+            let mut s = [0u8; 32];
+            s[0] = n;
+            s
+        };
+        assert_eq!(x25519_unclamped(small(1), small(1)), small(1));
+        assert_eq!(x25519_unclamped(small(2), small(1)), small(0));
+        assert_eq!(x25519_unclamped(small(3), small(1)), small(1));
+        assert_eq!(x25519_unclamped(small(1), small(9)), small(9));
+    }
+
+    #[test]
+    fn curve25519_clamps_secret() {
+        let mut u = [0u8; 32];
+        u[0] = 9;
+        for seed in 0..8 {
+            // Set every bit clamping clears and clear the one it sets.
+            let mut raw = test_scalar(seed);
+            raw[0] |= 7;
+            raw[31] = (raw[31] | 0x80) & !0x40;
+            assert_eq!(curve25519(raw, u), curve25519(clamp_scalar(raw), u));
+        }
+    }
+
+    #[test]
+    fn rfc7748_vectors() {
+        // RFC 7748, section 5.2.
+        let vectors = [
+            (
+                "a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4",
+                "e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c",
+                "c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552",
+            ),
+            (
+                "4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d",
+                "e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493",
+                "95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957",
+            ),
+        ];
+        for (k, u, out) in vectors.iter() {
+            let (k, u) = (from_hex(k).unwrap(), from_hex(u).unwrap());
+            assert_eq!(Ok(curve25519(k, u)), from_hex(out));
+        }
+    }
+
+    // RFC 7748, section 5.2: starting from k = u = 9, repeatedly set
+    // (k, u) = (X25519(k, u), k).
+    fn x25519_iterated(iterations: usize) -> [u8; 32] {
+        let mut k = [0u8; 32];
+        k[0] = 9;
+        let mut u = k;
+        for _ in 0..iterations {
+            let next = curve25519(k, u);
+            u = k;
+            k = next;
+        }
+        k
+    }
+
+    #[test]
+    fn rfc7748_iterated() {
+        assert_eq!(
+            Ok(x25519_iterated(1)),
+            from_hex(
+                "422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079"
+            )
+        );
+        assert_eq!(
+            Ok(x25519_iterated(1_000)),
+            from_hex(
+                "684cf59ba83309552800ef566f2f4d3c1c3887c49360e3875f2eb94d99532c51"
+            )
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn rfc7748_iterated_million() {
+        assert_eq!(
+            Ok(x25519_iterated(1_000_000)),
+            from_hex(
+                "7c3911e0ab2586fd864497297e575e6f3bc601c0883c30df5f4dd2d24f665424"
+            )
+        );
+    }
+
+    // Deterministic, non-trivial scalars below 2^255.
+    fn test_scalar(seed: u8) -> [u8; 32] {
+        let mut k = [0u8; 32];
+        for (i, b) in k.iter_mut().enumerate() {
+            *b = seed
+                .wrapping_mul(31)
+                .wrapping_add((i as u8).wrapping_mul(7));
+        }
+        k[31] &= 127;
+        k
+    }
+
+    #[test]
+    fn multiscalar_mul_matches_naive_sum() {
+        // P_i = s_i * B, so sum k_i * P_i = (sum k_i * s_i) * B.
+        let mut scalars = [[0u8; 32]; 20];
+        let mut points = [GeP3::zero(); 20];
+        let mut expected = [0u8; 32];
+        for i in 0..20 {
+            let s = test_scalar(2 * i as u8 + 1);
+            scalars[i] = test_scalar(2 * i as u8 + 2);
+            points[i] = ge_scalarmult_base(&s);
+            let acc = expected;
+            sc_muladd(&mut expected, &scalars[i], &s, &acc);
+
+            let r = multiscalar_mul(&scalars[..=i], &points[..=i]);
+            assert_eq!(r.to_bytes(), ge_scalarmult_base(&expected).to_bytes());
+        }
+    }
+
+    #[test]
+    fn multiscalar_mul_single_point() {
+        let k = test_scalar(5);
+        let p = ge_scalarmult_base(&test_scalar(6));
+        let r = multiscalar_mul(&[k], &[p]);
+        let expected = GeP2::double_scalarmult_vartime(&k, p, &[0; 32]);
+        assert_eq!(r.to_bytes(), expected.to_bytes());
+    }
+
+    #[test]
+    fn multiscalar_mul_empty_is_identity() {
+        assert_eq!(
+            multiscalar_mul(&[], &[]).to_bytes(),
+            GeP3::zero().to_bytes()
+        );
+    }
+
+    #[test]
+    fn to_montgomery_matches_curve25519_pk() {
+        for seed in 0..8 {
+            let mut sk = test_scalar(seed);
+            sk[0] &= 248;
+            sk[31] |= 64;
+            let u = ge_scalarmult_base(&sk).to_montgomery();
+            assert_eq!(u.to_bytes(), curve25519_pk(sk));
+        }
+    }
+
+    #[test]
+    fn x25519_pk_to_edwards_round_trip() {
+        for seed in 0..8 {
+            let sk = clamp_scalar(test_scalar(seed));
+            let ed = ge_scalarmult_base(&sk).to_bytes();
+            let sign_bit = ed[31] >> 7 == 1;
+            let u = x25519_base(sk);
+            assert_eq!(x25519_pk_to_edwards(&u, sign_bit), Some(ed));
+
+            // The other sign gives the negated point.
+            let mut negated = ed;
+            negated[31] ^= 0x80;
+            assert_eq!(x25519_pk_to_edwards(&u, !sign_bit), Some(negated));
+            let point = GeP3::from_bytes_vartime(&negated).unwrap();
+            assert!(point == ge_scalarmult_base(&sk).neg());
+        }
+
+        // u = -1 has no image; y = (u - 1) / (u + 1) divides by zero.
+        let mut minus_one = [0xff; 32];
+        minus_one[0] = 0xec;
+        minus_one[31] = 0x7f;
+        assert_eq!(x25519_pk_to_edwards(&minus_one, false), None);
+    }
+
+    #[test]
+    fn to_montgomery_of_identity_is_zero() {
+        assert_eq!(GeP3::zero().to_montgomery().to_bytes(), [0; 32]);
+
+        // (0, -1), the point of order 2, has the genuine image u = 0.
+        let mut minus_one = [0xff; 32];
+        minus_one[0] = 0xec;
+        minus_one[31] = 0x7f;
+        let order_two = GeP3::from_bytes_vartime(&minus_one).unwrap();
+        assert_eq!(order_two.to_montgomery().to_bytes(), [0; 32]);
+
+        // Fed into X25519, infinity gives the all-zero shared secret.
+        let u = GeP3::zero().to_montgomery().to_bytes();
+        assert_eq!(curve25519([0x5a; 32], u), [0; 32]);
+    }
+
+    #[test]
+    fn scalar_reduce_matches_sc_reduce() {
+        let mut wide = [0u8; 64];
+        for (i, b) in wide.iter_mut().enumerate() {
+            *b = (i as u8).wrapping_mul(151).wrapping_add(3);
+        }
+        let mut expected = wide;
+        sc_reduce(&mut expected);
+        assert_eq!(scalar_reduce(wide), expected[..32]);
+
+        // 2^256 - 1 needs more than one subtraction of l.
+        let mut all_ones = [0u8; 64];
+        all_ones[..32].copy_from_slice(&[0xff; 32]);
+        let mut expected = all_ones;
+        sc_reduce(&mut expected);
+        assert_eq!(scalar_reduce(all_ones), expected[..32]);
+    }
+
+    #[test]
+    fn scalar_muladd_matches_sc_muladd() {
+        let (a, b, c) = (test_scalar(1), test_scalar(2), test_scalar(3));
+        let mut expected = [0u8; 32];
+        sc_muladd(&mut expected, &a, &b, &c);
+        assert_eq!(scalar_muladd(&a, &b, &c), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "sc_reduce: s must be 64 bytes")]
+    fn sc_reduce_rejects_short_input() { sc_reduce(&mut [0u8; 32]); }
+
+    #[test]
+    #[should_panic(expected = "sc_muladd: b must be 32 bytes")]
+    fn sc_muladd_rejects_short_input() {
+        sc_muladd(&mut [0u8; 32], &[0u8; 32], &[0u8; 31], &[0u8; 32]);
+    }
+
+    #[test]
+    fn from_bytes_negate_ct_matches_vartime() {
+        let mut encodings = std::vec![[0u8; 32], [0xff; 32]];
+        for i in 1..=20u8 {
+            encodings.push(ge_scalarmult_base(&test_scalar(i)).to_bytes());
+            encodings.push([i; 32]);
+        }
+        let mut identity = [0u8; 32];
+        identity[0] = 1;
+        encodings.push(identity);
+
+        for s in encodings.iter() {
+            let ct = GeP3::from_bytes_negate_ct(s).map(|p| p.to_bytes());
+            let vartime =
+                GeP3::from_bytes_negate_vartime(s).map(|p| p.to_bytes());
+            assert_eq!(ct, vartime);
+        }
+        assert!(GeP3::from_bytes_negate_ct(&[0u8; 31]).is_none());
+    }
+
+    #[test]
+    fn try_from_bytes_checks_length() {
+        let bytes = [7u8; 33];
+        let fe = FieldElement::try_from_bytes(&bytes[..32]).unwrap();
+        assert!(fe == FieldElement::from_bytes(&[7; 32]));
+        assert!(FieldElement::try_from_bytes(&bytes[..31]).is_none());
+        assert!(FieldElement::try_from_bytes(&bytes).is_none());
+
+        assert!(GeP3::from_bytes_negate_vartime(&bytes[..31]).is_none());
+    }
+
+    #[test]
+    fn curve25519_ignores_high_bit_of_public() {
+        let sk = curve25519_sk(Some([0x5a; 32])).unwrap();
+        let pk = curve25519_pk(curve25519_sk(Some([0xa5; 32])).unwrap());
+        let mut pk_high = pk;
+        pk_high[31] |= 128;
+
+        assert!(
+            FieldElement::from_bytes_masked(&pk_high)
+                == FieldElement::from_bytes(&pk)
+        );
+        assert_eq!(curve25519(sk, pk_high), curve25519(sk, pk));
+    }
+
+    fn small_multiple(k: u8) -> GeP3 {
+        let mut scalar = [0u8; 32];
+        scalar[0] = k;
+        ge_scalarmult_base(&scalar)
+    }
+
+    #[test]
+    fn tables_for_another_generator() {
+        // H = 7B stands in for an independent generator.
+        let h = small_multiple(7);
+        let mut precomp = [GePrecomp::from_p3(&h); 8];
+        let mut cached = [h.to_cached(); 8];
+        let mut multiple = h;
+        for i in 1..8 {
+            multiple = (multiple + h.to_cached()).to_p3();
+            precomp[i] = GePrecomp::from_p3(&multiple);
+            cached[i] = multiple.to_cached();
+        }
+
+        for b in -8i8..=8 {
+            let mut k = [0u8; 32];
+            k[0] = b.unsigned_abs();
+            let mut expected = ge_scalarmult_ct(&k, &h);
+            if b < 0 {
+                expected = expected.neg();
+            }
+
+            let from_precomp =
+                (GeP3::zero() + ge_precomp_select(&precomp, b)).to_p3();
+            let from_cached =
+                (GeP3::zero() + ge_cached_select(&cached, b)).to_p3();
+            assert!(from_precomp == expected);
+            assert!(from_cached == expected);
+        }
+    }
+
+    #[test]
+    fn select_picks_signed_multiples() {
+        let mut cached = [GeP3::zero().to_cached(); 8];
+        for (i, entry) in cached.iter_mut().enumerate() {
+            *entry = small_multiple(i as u8 + 1).to_cached();
+        }
+        let identity = GeP3::zero().to_bytes();
+
+        for b in -8i8..=8 {
+            let k = b.unsigned_abs();
+            let from_cached =
+                (GeP3::zero() + ge_cached_select(&cached, b)).to_p3();
+            let from_precomp = (GeP3::zero()
+                + ge_precomp_select(&GE_PRECOMP_BASE[0], b))
+            .to_p3();
+
+            if b >= 0 {
+                assert_eq!(
+                    from_cached.to_bytes(),
+                    small_multiple(k).to_bytes()
+                );
+                assert_eq!(
+                    from_precomp.to_bytes(),
+                    small_multiple(k).to_bytes()
+                );
+            } else {
+                // kB + (-k)B is the identity.
+                let cancel =
+                    |p: GeP3| (small_multiple(k) + p.to_cached()).to_p3();
+                assert_eq!(cancel(from_cached).to_bytes(), identity);
+                assert_eq!(cancel(from_precomp).to_bytes(), identity);
+            }
+        }
+    }
+
+    struct FailingRng;
+
+    // Every method fails, the infallible ones by panicking with the error,
+    // as OsRng does.
+    impl RngCore for FailingRng {
+        fn next_u32(&mut self) -> u32 { impls::next_u32_via_fill(self) }
+
+        fn next_u64(&mut self) -> u64 { impls::next_u64_via_fill(self) }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            self.try_fill_bytes(dest).unwrap()
+        }
+
+        fn try_fill_bytes(&mut self, _: &mut [u8]) -> Result<(), RndError> {
+            Err(RndError::from(
+                NonZeroU32::new(RndError::CUSTOM_START).unwrap(),
+            ))
+        }
+    }
+
+    #[test]
+    fn rng_failure_is_reported() {
+        assert_eq!(curve25519_sk_from_rng(&mut FailingRng), Err(Error::Rng));
+    }
+
+    // Not actually secure, but deterministic, which is what the test needs.
+    struct CountingRng(u8);
+
+    impl RngCore for CountingRng {
+        fn next_u32(&mut self) -> u32 { impls::next_u32_via_fill(self) }
+
+        fn next_u64(&mut self) -> u64 { impls::next_u64_via_fill(self) }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for b in dest {
+                *b = self.0;
+                self.0 = self.0.wrapping_add(1);
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), RndError> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for CountingRng {}
+
+    #[test]
+    fn sk_with_rng_is_reproducible() {
+        let sk = curve25519_sk_with_rng(&mut CountingRng(7));
+        assert_eq!(sk, curve25519_sk_with_rng(&mut CountingRng(7)));
+        assert_ne!(sk, curve25519_sk_with_rng(&mut CountingRng(8)));
+
+        let mut bytes = [0u8; 32];
+        CountingRng(7).fill_bytes(&mut bytes);
+        assert_eq!(sk, curve25519_sk(Some(bytes)).unwrap());
+    }
+
+    #[test]
+    fn sk_with_chacha_is_clamped() {
+        use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+        let mut rng = ChaCha20Rng::from_seed([42; 32]);
+        let first = curve25519_sk_with_rng(&mut rng);
+        for sk in core::iter::once(first)
+            .chain((0..15).map(|_| curve25519_sk_with_rng(&mut rng)))
+        {
+            assert_eq!(sk[0] & 7, 0);
+            assert_ne!(sk[31] & 64, 0);
+            assert_eq!(sk[31] & 128, 0);
+        }
+
+        let mut rng = ChaCha20Rng::from_seed([42; 32]);
+        assert_eq!(curve25519_sk_with_rng(&mut rng), first);
+    }
 }
@@ -0,0 +1,227 @@
+//! The ristretto255 prime-order group.
+//!
+//! Ristretto maps the Edwards curve, which has cofactor 8, onto a group of
+//! prime order `l` by treating points that differ by a small torsion
+//! component as the same element, and gives every element exactly one
+//! 32-byte encoding. It follows [RFC 9496].
+//!
+//! [RFC 9496]: https://www.rfc-editor.org/rfc/rfc9496
+
+use crate::{
+    hazmat::{ge_scalarmult_base, GeP3},
+    multiscalar_mul,
+    statics::{FE_D, FE_INVSQRT_A_MINUS_D, FE_ONE, FE_SQRTM1},
+    FieldElement,
+};
+use core::{
+    cmp::{Eq, PartialEq},
+    ops::Add,
+};
+
+/// An element of the ristretto255 group.
+///
+/// Equality compares group elements, not the underlying Edwards points.
+#[derive(Clone, Copy)]
+pub struct RistrettoPoint(GeP3);
+
+impl RistrettoPoint {
+    /// The identity element, encoded as 32 zero bytes.
+    pub fn identity() -> RistrettoPoint { RistrettoPoint(GeP3::zero()) }
+
+    /// `scalar * B`, with `B` the ristretto255 generator.
+    ///
+    /// The scalar is little-endian and must satisfy `scalar[31] <= 127`.
+    pub fn mul_base(scalar: &[u8; 32]) -> RistrettoPoint {
+        RistrettoPoint(ge_scalarmult_base(scalar))
+    }
+
+    /// `scalar * self`.
+    ///
+    /// The scalar is little-endian and must satisfy `scalar[31] <= 127`.
+    /// This is **not** constant time, only use it with public scalars.
+    pub fn mul_vartime(&self, scalar: &[u8; 32]) -> RistrettoPoint {
+        RistrettoPoint(multiscalar_mul(&[*scalar], &[self.0]))
+    }
+
+    /// Encodes the element into its canonical 32 bytes.
+    pub fn compress(&self) -> [u8; 32] {
+        let GeP3 { x, y, z, t } = self.0;
+
+        let u1 = (z + y) * (z - y);
+        let u2 = x * y;
+        let (_, invsqrt) =
+            FieldElement::sqrt_ratio_m1(&FE_ONE, &(u1 * u2.square()));
+        let den1 = invsqrt * u1;
+        let den2 = invsqrt * u2;
+        let z_inv = den1 * den2 * t;
+
+        let ix = x * FE_SQRTM1;
+        let iy = y * FE_SQRTM1;
+        let enchanted_denominator = den1 * FE_INVSQRT_A_MINUS_D;
+
+        let rotate = i32::from((t * z_inv).is_negative());
+        let mut x = x;
+        let mut y = y;
+        let mut den_inv = den2;
+        x.maybe_set(&iy, rotate);
+        y.maybe_set(&ix, rotate);
+        den_inv.maybe_set(&enchanted_denominator, rotate);
+
+        y.conditional_negate(i32::from((x * z_inv).is_negative()));
+
+        (den_inv * (z - y)).abs().to_bytes()
+    }
+
+    /// Decodes an element, returning `None` unless `bytes` is the
+    /// canonical encoding of a ristretto255 element.
+    pub fn decompress(bytes: &[u8; 32]) -> Option<RistrettoPoint> {
+        // s must be a canonical, non-negative field element.
+        let s = FieldElement::from_bytes(bytes);
+        if s.to_bytes() != *bytes || s.is_negative() {
+            return None;
+        }
+
+        let ss = s.square();
+        let u1 = FE_ONE - ss;
+        let u2 = FE_ONE + ss;
+        let u2_sqr = u2.square();
+        let v = (FE_D * u1.square()).neg() - u2_sqr;
+
+        let (was_square, invsqrt) =
+            FieldElement::sqrt_ratio_m1(&FE_ONE, &(v * u2_sqr));
+        let den_x = invsqrt * u2;
+        let den_y = invsqrt * den_x * v;
+
+        let x = (s + s) * den_x;
+        let x = x.abs();
+        let y = u1 * den_y;
+        let t = x * y;
+
+        if !was_square || t.is_negative() || !y.is_nonzero() {
+            return None;
+        }
+
+        Some(RistrettoPoint(GeP3 { x, y, z: FE_ONE, t }))
+    }
+}
+
+impl Add for RistrettoPoint {
+    type Output = RistrettoPoint;
+
+    fn add(self, rhs: RistrettoPoint) -> RistrettoPoint {
+        RistrettoPoint(self.0 + rhs.0)
+    }
+}
+
+impl PartialEq for RistrettoPoint {
+    // Two representatives are the same element iff x1*y2 == y1*x2 or
+    // y1*y2 == x1*x2.
+    fn eq(&self, other: &RistrettoPoint) -> bool {
+        let (p, q) = (&self.0, &other.0);
+        let same_xy = p.x * q.y == p.y * q.x;
+        let same_yx = p.y * q.y == p.x * q.x;
+        same_xy | same_yx
+    }
+}
+
+impl Eq for RistrettoPoint {}
+
+#[cfg(test)]
+mod tests {
+    use super::RistrettoPoint;
+    use crate::{hazmat::GeP3, util::from_hex};
+
+    // RFC 9496, A.1: encodings of 0*B through 15*B.
+    static SMALL_MULTIPLES: [&str; 16] = [
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+        "6a493210f7499cd17fecb510ae0cea23a110e8d5b901f8acadd3095c73a3b919",
+        "94741f5d5d52755ece4f23f044ee27d5d1ea1e2bd196b462166b16152a9d0259",
+        "da80862773358b466ffadfe0b3293ab3d9fd53c5ea6c955358f568322daf6a57",
+        "e882b131016b52c1d3337080187cf768423efccbb517bb495ab812c4160ff44e",
+        "f64746d3c92b13050ed8d80236a7f0007c3b3f962f5ba793d19a601ebb1df403",
+        "44f53520926ec81fbd5a387845beb7df85a96a24ece18738bdcfa6a7822a176d",
+        "903293d8f2287ebe10e2374dc1a53e0bc887e592699f02d077d5263cdd55601c",
+        "02622ace8f7303a31cafc63f8fc48fdc16e1c8c8d234b2f0d6685282a9076031",
+        "20706fd788b2720a1ed2a5dad4952b01f413bcf0e7564de8cdc816689e2db95f",
+        "bce83f8ba5dd2fa572864c24ba1810f9522bc6004afe95877ac73241cafdab42",
+        "e4549ee16b9aa03099ca208c67adafcafa4c3f3e4e5303de6026e3ca8ff84460",
+        "aa52e000df2e16f55fb1032fc33bc42742dad6bd5a8fc0be0167436c5948501f",
+        "46376b80f409b29dc2b5f6f0c52591990896e5716f41477cd30085ab7f10301e",
+        "e0c418f7c8d9c4cdd7395b93ea124f3ad99021bb681dfc3302a9d99a2e53e64e",
+    ];
+
+    // RFC 9496, A.2: non-canonical, negative, non-square and s = -1
+    // encodings that must be rejected.
+    static BAD_ENCODINGS: [&str; 11] = [
+        "00ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "f3ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "0100000000000000000000000000000000000000000000000000000000000000",
+        "01ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "ed57ffd8c914fb201471d1c3d245ce3c746fcbe63a3679d51b6a516ebebe0e20",
+        "c34c4e1826e5d403b78e246e88aa051c36ccf0aafebffe137d148a2bf9104562",
+        "26948d35ca62e643e26a83177332e6b6afeb9d08e4268b650f1f5bbd8d81d371",
+        "3eb858e78f5a7254d8c9731174a94f76755fd3941c0ac93735c07ba14579630e",
+        "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+    ];
+
+    #[test]
+    fn small_multiples_of_basepoint() {
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        let b = RistrettoPoint::mul_base(&one);
+
+        let mut p = RistrettoPoint::identity();
+        for (i, encoding) in SMALL_MULTIPLES.iter().enumerate() {
+            let expected = from_hex(encoding).unwrap();
+            assert_eq!(p.compress(), expected);
+
+            let mut k = [0u8; 32];
+            k[0] = i as u8;
+            assert_eq!(RistrettoPoint::mul_base(&k).compress(), expected);
+            assert_eq!(b.mul_vartime(&k).compress(), expected);
+
+            let q = RistrettoPoint::decompress(&expected).unwrap();
+            assert!(q == p);
+            assert_eq!(q.compress(), expected);
+
+            p = p + b;
+        }
+    }
+
+    #[test]
+    fn bad_encodings_are_rejected() {
+        for encoding in BAD_ENCODINGS.iter() {
+            assert!(RistrettoPoint::decompress(&from_hex(encoding).unwrap())
+                .is_none());
+        }
+    }
+
+    #[test]
+    fn equality_ignores_torsion() {
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        let b = RistrettoPoint::mul_base(&one);
+
+        // The Edwards points of order 2 and 4: (0, -1) and (+-i, 0).
+        let mut order_two = [0xff; 32];
+        order_two[0] = 0xec;
+        order_two[31] = 0x7f;
+        let order_four = [0u8; 32];
+        let mut order_four_neg = [0u8; 32];
+        order_four_neg[31] = 0x80;
+
+        for encoding in [order_two, order_four, order_four_neg].iter() {
+            let t = GeP3::from_bytes_vartime(encoding).unwrap();
+            assert!(!t.is_identity());
+            let shifted = RistrettoPoint(b.0 + t);
+            assert!(shifted.0 != b.0);
+            assert!(shifted == b);
+            assert_eq!(shifted.compress(), b.compress());
+            assert!(RistrettoPoint(t) == RistrettoPoint::identity());
+        }
+        assert!(b != RistrettoPoint::identity());
+    }
+}
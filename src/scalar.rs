@@ -0,0 +1,350 @@
+use crate::{
+    scalar_muladd, scalar_reduce,
+    util::{fixed_time_eq, zeroize},
+    Error, GROUP_ORDER,
+};
+use core::{
+    cmp::{Eq, PartialEq},
+    convert::TryFrom,
+    ops::{Add, Neg, Sub},
+};
+use rand_core::{CryptoRng, RngCore};
+
+// l - 1, little-endian. Multiplying by it negates modulo l.
+static L_MINUS_ONE: [u8; 32] = [
+    0xec, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2,
+    0xde, 0xf9, 0xde, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+];
+
+static ONE: [u8; 32] = [
+    1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0,
+];
+
+/// An integer modulo the group order
+/// `l = 2^252 + 27742317777372353535851937790883648493`, stored as its
+/// 32-byte little-endian encoding.
+///
+/// The stored bytes are always reduced, i.e. below `l`.
+#[derive(Clone, Copy)]
+pub struct Scalar([u8; 32]);
+
+impl Scalar {
+    /// The scalar zero.
+    pub const ZERO: Scalar = Scalar([0; 32]);
+
+    /// Reduce 32 little-endian bytes modulo `l`. Every input is accepted.
+    pub fn from_bytes_mod_order(bytes: [u8; 32]) -> Scalar {
+        let mut wide = [0u8; 64];
+        wide[..32].copy_from_slice(&bytes);
+        Scalar(scalar_reduce(wide))
+    }
+
+    /// Reduce 64 little-endian bytes modulo `l`. Every input is accepted.
+    ///
+    /// With uniformly random input the result is uniform in `[0, l)` up to
+    /// a bias of about 2^-259, unlike reducing 32 bytes.
+    pub fn from_bytes_mod_order_wide(bytes: [u8; 64]) -> Scalar {
+        Scalar(scalar_reduce(bytes))
+    }
+
+    /// Reduce a 64-byte little-endian hash modulo `l`, e.g. the SHA-512
+    /// digest of a Schnorr or Ed25519 challenge.
+    pub fn from_hash(hash: [u8; 64]) -> Scalar {
+        Scalar::from_bytes_mod_order_wide(hash)
+    }
+
+    /// A uniformly random scalar, e.g. a Schnorr nonce or a blinding
+    /// factor.
+    ///
+    /// Samples 64 bytes rather than 32: l is just above 2^252, so 32 random
+    /// bytes reduced modulo l would favour the low residues, while with
+    /// 512 bits the bias is negligible.
+    pub fn random<R: RngCore + CryptoRng>(rng: &mut R) -> Scalar {
+        let mut bytes = [0u8; 64];
+        rng.fill_bytes(&mut bytes);
+        let s = Scalar::from_bytes_mod_order_wide(bytes);
+        zeroize(&mut bytes);
+        s
+    }
+
+    /// Whether `bytes` encodes an integer below `l`, the condition
+    /// RFC 8032 puts on the `S` half of a signature.
+    ///
+    /// Runs in constant time.
+    pub fn is_canonical(bytes: &[u8; 32]) -> bool {
+        // Subtract l and look at the final borrow.
+        let mut borrow = 0i16;
+        for (&b, &l) in bytes.iter().zip(GROUP_ORDER.iter()) {
+            let diff = i16::from(b) - i16::from(l) - borrow;
+            borrow = (diff >> 8) & 1;
+        }
+        borrow == 1
+    }
+
+    /// Parse 32 little-endian bytes that must already be reduced, e.g. the
+    /// `S` half of an RFC 8032 signature. Returns `None` for values of `l`
+    /// and above instead of reducing them, so every scalar has exactly one
+    /// accepted encoding.
+    ///
+    /// Runs in constant time, see [`is_canonical`](Scalar::is_canonical).
+    pub fn from_canonical_bytes(bytes: [u8; 32]) -> Option<Scalar> {
+        if Scalar::is_canonical(&bytes) {
+            Some(Scalar(bytes))
+        } else {
+            None
+        }
+    }
+
+    /// Reduce 32 big-endian bytes modulo `l`, for peers that send scalars
+    /// most significant byte first. Every input is accepted.
+    pub fn from_bytes_be(mut bytes: [u8; 32]) -> Scalar {
+        bytes.reverse();
+        Scalar::from_bytes_mod_order(bytes)
+    }
+
+    /// The big-endian encoding of the scalar, the byte-reversal of
+    /// [`to_bytes`](Scalar::to_bytes).
+    pub fn to_bytes_be(&self) -> [u8; 32] {
+        let mut bytes = self.0;
+        bytes.reverse();
+        bytes
+    }
+
+    /// A reference to the little-endian encoding of the scalar.
+    pub fn as_bytes(&self) -> &[u8; 32] { &self.0 }
+
+    /// The little-endian encoding of the scalar.
+    pub fn to_bytes(&self) -> [u8; 32] { self.0 }
+}
+
+// a * b + c mod l
+fn muladd(a: &[u8; 32], b: &[u8; 32], c: &[u8; 32]) -> Scalar {
+    Scalar(scalar_muladd(a, b, c))
+}
+
+impl Add for Scalar {
+    type Output = Scalar;
+
+    fn add(self, rhs: Scalar) -> Scalar { muladd(&ONE, &self.0, &rhs.0) }
+}
+
+impl Neg for Scalar {
+    type Output = Scalar;
+
+    // -s = (l - 1) * s mod l, which also maps zero to zero.
+    fn neg(self) -> Scalar { muladd(&L_MINUS_ONE, &self.0, &Scalar::ZERO.0) }
+}
+
+impl Sub for Scalar {
+    type Output = Scalar;
+
+    // a - b = a + (-b) = (l - 1) * b + a mod l
+    fn sub(self, rhs: Scalar) -> Scalar {
+        muladd(&L_MINUS_ONE, &rhs.0, &self.0)
+    }
+}
+
+impl PartialEq for Scalar {
+    fn eq(&self, other: &Scalar) -> bool { fixed_time_eq(&self.0, &other.0) }
+}
+
+impl Eq for Scalar {}
+
+/// Strict parsing: the slice must hold exactly 32 bytes encoding a value
+/// below `l`. Use [`Scalar::from_bytes_mod_order`] to reduce instead.
+impl TryFrom<&[u8]> for Scalar {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Scalar, Error> {
+        if bytes.len() != 32 {
+            return Err(Error::InvalidLength);
+        }
+        let mut s = [0u8; 32];
+        s.copy_from_slice(bytes);
+        Scalar::from_canonical_bytes(s).ok_or(Error::NonCanonical)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Scalar, GROUP_ORDER};
+    use crate::{scalar_reduce, Error};
+    use core::convert::TryFrom;
+
+    #[test]
+    fn from_bytes_mod_order_reduces() {
+        assert!(Scalar::from_bytes_mod_order(GROUP_ORDER) == Scalar::ZERO);
+
+        let mut l_plus_one = GROUP_ORDER;
+        l_plus_one[0] += 1;
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        assert_eq!(Scalar::from_bytes_mod_order(l_plus_one).to_bytes(), one);
+    }
+
+    #[test]
+    fn from_hash_matches_scalar_reduce() {
+        let mut hash = [0u8; 64];
+        for (i, b) in hash.iter_mut().enumerate() {
+            *b = (i as u8).wrapping_mul(151).wrapping_add(3);
+        }
+        let s = Scalar::from_hash(hash);
+        assert_eq!(s.to_bytes(), scalar_reduce(hash));
+        assert!(Scalar::is_canonical(s.as_bytes()));
+
+        let max = Scalar::from_hash([0xff; 64]);
+        assert!(Scalar::is_canonical(max.as_bytes()));
+    }
+
+    #[test]
+    fn random_is_canonical() {
+        use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+        let mut rng = ChaCha20Rng::from_seed([7; 32]);
+        let first = Scalar::random(&mut rng);
+        let mut high_nibbles = 0u32;
+        for _ in 0..1000 {
+            let s = Scalar::random(&mut rng);
+            assert!(Scalar::is_canonical(s.as_bytes()));
+            high_nibbles |= 1 << s.as_bytes()[31];
+        }
+        // The top byte is at most 0x10 and all of 0..16 show up.
+        assert_eq!(high_nibbles & 0xffff, 0xffff);
+
+        let mut rng = ChaCha20Rng::from_seed([7; 32]);
+        let mut bytes = [0u8; 64];
+        rand_core::RngCore::fill_bytes(&mut rng, &mut bytes);
+        assert!(first == Scalar::from_bytes_mod_order_wide(bytes));
+    }
+
+    #[test]
+    fn try_from_is_strict() {
+        let mut l_minus_one = GROUP_ORDER;
+        l_minus_one[0] -= 1;
+        let s = Scalar::try_from(&l_minus_one[..]).unwrap();
+        assert_eq!(s.as_bytes(), &l_minus_one);
+
+        assert_eq!(
+            Scalar::try_from(&GROUP_ORDER[..]).err(),
+            Some(Error::NonCanonical)
+        );
+        assert_eq!(
+            Scalar::try_from(&[0xff; 32][..]).err(),
+            Some(Error::NonCanonical)
+        );
+    }
+
+    #[test]
+    fn from_canonical_bytes_rejects_unreduced() {
+        let mut l_minus_one = GROUP_ORDER;
+        l_minus_one[0] -= 1;
+        let s = Scalar::from_canonical_bytes(l_minus_one).unwrap();
+        assert_eq!(s.to_bytes(), l_minus_one);
+        assert!(Scalar::from_canonical_bytes([0; 32]) == Some(Scalar::ZERO));
+
+        // l + 1 would reduce to 1; the strict parser refuses it.
+        let mut l_plus_one = GROUP_ORDER;
+        l_plus_one[0] += 1;
+        assert!(Scalar::from_canonical_bytes(GROUP_ORDER).is_none());
+        assert!(Scalar::from_canonical_bytes(l_plus_one).is_none());
+        assert!(Scalar::from_canonical_bytes([0xff; 32]).is_none());
+    }
+
+    #[test]
+    fn try_from_checks_length() {
+        assert_eq!(
+            Scalar::try_from(&[0u8; 31][..]).err(),
+            Some(Error::InvalidLength)
+        );
+        assert_eq!(
+            Scalar::try_from(&[0u8; 33][..]).err(),
+            Some(Error::InvalidLength)
+        );
+    }
+
+    // 32 xorshift bytes, most of them encoding integers above l.
+    fn test_bytes(state: &mut u64) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for b in bytes.iter_mut() {
+            *state ^= *state << 13;
+            *state ^= *state >> 7;
+            *state ^= *state << 17;
+            *b = *state as u8;
+        }
+        bytes
+    }
+
+    // A handful of unrelated scalars spread over [0, l).
+    fn test_scalars() -> [Scalar; 8] {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        [(); 8].map(|_| Scalar::from_bytes_mod_order(test_bytes(&mut state)))
+    }
+
+    #[test]
+    fn big_endian_matches_little_endian() {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        for _ in 0..16 {
+            let le = test_bytes(&mut state);
+            let mut be = le;
+            be.reverse();
+
+            let s = Scalar::from_bytes_be(be);
+            assert!(s == Scalar::from_bytes_mod_order(le));
+            let mut round_trip = s.to_bytes_be();
+            round_trip.reverse();
+            assert_eq!(round_trip, s.to_bytes());
+        }
+
+        let mut l_be = GROUP_ORDER;
+        l_be.reverse();
+        assert!(Scalar::from_bytes_be(l_be) == Scalar::ZERO);
+    }
+
+    #[test]
+    fn neg_zero_is_zero() {
+        assert!(-Scalar::ZERO == Scalar::ZERO);
+    }
+
+    #[test]
+    fn neg_and_sub() {
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        let one = Scalar::from_bytes_mod_order(one);
+        let mut l_minus_one = GROUP_ORDER;
+        l_minus_one[0] -= 1;
+        assert_eq!((-one).to_bytes(), l_minus_one);
+
+        let scalars = test_scalars();
+        for &a in scalars.iter() {
+            assert!(a - a == Scalar::ZERO);
+            assert!(a + (-a) == Scalar::ZERO);
+            assert!(-(-a) == a);
+            for &b in scalars.iter() {
+                assert!(a - b == a + (-b));
+                assert!((a - b) + b == a);
+                assert!(a - b == -(b - a));
+            }
+        }
+    }
+
+    #[test]
+    fn is_canonical_boundaries() {
+        let mut l_minus_one = GROUP_ORDER;
+        l_minus_one[0] -= 1;
+        let mut l_plus_one = GROUP_ORDER;
+        l_plus_one[0] += 1;
+
+        assert!(Scalar::is_canonical(&[0; 32]));
+        assert!(Scalar::is_canonical(&l_minus_one));
+        assert!(!Scalar::is_canonical(&GROUP_ORDER));
+        assert!(!Scalar::is_canonical(&l_plus_one));
+        assert!(!Scalar::is_canonical(&[0xff; 32]));
+
+        // Larger than l in a low byte but smaller in the top one.
+        let mut below = [0xff; 32];
+        below[31] = 0x0f;
+        assert!(Scalar::is_canonical(&below));
+    }
+}
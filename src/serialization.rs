@@ -0,0 +1,244 @@
+//! `serde` support for the byte newtypes.
+//!
+//! Every type goes through its canonical encoding, 32 bytes for keys,
+//! points and scalars: a byte string for binary formats and lowercase hex
+//! digits for human-readable ones.
+
+#[cfg(feature = "vrf")]
+use crate::vrf::Proof;
+use crate::{
+    edwards::CompressedEdwardsY, ristretto::RistrettoPoint, util::from_hex,
+    Error, Scalar, X25519PublicKey, X25519SecretKey,
+};
+use core::{convert::TryFrom, fmt};
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+// Lowercase hex, written straight into the serializer.
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.iter().try_for_each(|b| write!(f, "{:02x}", b))
+    }
+}
+
+fn serialize_bytes<S: Serializer>(
+    bytes: &[u8],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.collect_str(&Hex(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+struct BytesVisitor<const N: usize>;
+
+impl<'de, const N: usize> Visitor<'de> for BytesVisitor<N> {
+    type Value = [u8; N];
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} bytes or {} hex digits", N, 2 * N)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<[u8; N], E> {
+        from_hex(v).map_err(|e| match e {
+            Error::InvalidLength => E::invalid_length(v.len(), &self),
+            _ => E::invalid_value(de::Unexpected::Str(v), &self),
+        })
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<[u8; N], E> {
+        <[u8; N]>::try_from(v).map_err(|_| E::invalid_length(v.len(), &self))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> Result<[u8; N], A::Error> {
+        let mut bytes = [0u8; N];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(de::Error::invalid_length(N + 1, &self));
+        }
+        Ok(bytes)
+    }
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>, const N: usize>(
+    deserializer: D,
+) -> Result<[u8; N], D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(BytesVisitor)
+    } else {
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+// Implements `Serialize`/`Deserialize` through a fixed-size encoding.
+// `$decode` returns `None` for byte strings that aren't a valid encoding.
+macro_rules! serde_via_bytes {
+    (
+        $ty:ty,
+        encode: |$this:ident| $encode:expr,
+        decode: |$bytes:ident| $decode:expr,
+    ) => {
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(
+                &self,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                let $this = self;
+                serialize_bytes(&$encode, serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<$ty, D::Error> {
+                let $bytes = deserialize_bytes(deserializer)?;
+                $decode.ok_or_else(|| {
+                    de::Error::custom(concat!(
+                        "invalid ",
+                        stringify!($ty),
+                        " encoding"
+                    ))
+                })
+            }
+        }
+    };
+}
+
+serde_via_bytes! {
+    X25519SecretKey,
+    encode: |sk| *sk.as_bytes(),
+    decode: |bytes| Some(X25519SecretKey::from_bytes(bytes)),
+}
+
+serde_via_bytes! {
+    X25519PublicKey,
+    encode: |pk| *pk.as_bytes(),
+    decode: |bytes| Some(X25519PublicKey::from_bytes(bytes)),
+}
+
+// Not validated, like `From<[u8; 32]>`: the bytes become a point only on
+// `decompress`.
+serde_via_bytes! {
+    CompressedEdwardsY,
+    encode: |point| point.to_bytes(),
+    decode: |bytes| Some(CompressedEdwardsY::from(bytes)),
+}
+
+serde_via_bytes! {
+    RistrettoPoint,
+    encode: |point| point.compress(),
+    decode: |bytes| RistrettoPoint::decompress(&bytes),
+}
+
+serde_via_bytes! {
+    Scalar,
+    encode: |s| s.to_bytes(),
+    decode: |bytes| Scalar::from_canonical_bytes(bytes),
+}
+
+#[cfg(feature = "vrf")]
+serde_via_bytes! {
+    Proof,
+    encode: |proof| proof.to_bytes(),
+    decode: |bytes| Some(Proof::from_bytes(bytes)),
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use crate::{
+        edwards::CompressedEdwardsY, ristretto::RistrettoPoint, X25519PublicKey,
+    };
+    use serde::{
+        de::value::{BytesDeserializer, Error},
+        Deserialize,
+    };
+    use std::format;
+
+    #[test]
+    fn json_uses_hex() {
+        let pk = X25519PublicKey::from_bytes([0xab; 32]);
+        let json = serde_json::to_string(&pk).unwrap();
+        assert_eq!(json, format!("\"{}\"", "ab".repeat(32)));
+
+        let back: X25519PublicKey = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.as_bytes(), pk.as_bytes());
+    }
+
+    #[test]
+    fn wrong_length_is_rejected() {
+        let short = format!("\"{}\"", "ab".repeat(31));
+        assert!(serde_json::from_str::<X25519PublicKey>(&short).is_err());
+
+        let bytes = [0u8; 31];
+        let de = BytesDeserializer::<Error>::new(&bytes);
+        assert!(X25519PublicKey::deserialize(de).is_err());
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let bytes = [7u8; 32];
+        let de = BytesDeserializer::<Error>::new(&bytes);
+        let pk = X25519PublicKey::deserialize(de).unwrap();
+        assert_eq!(pk.as_bytes(), &bytes);
+    }
+
+    #[test]
+    fn compressed_point_is_not_validated() {
+        // y = 2 isn't on the curve, but the bytes round-trip as they are.
+        let mut two = [0u8; 32];
+        two[0] = 2;
+        let compressed = CompressedEdwardsY::from(two);
+        let json = serde_json::to_string(&compressed).unwrap();
+        assert_eq!(json, format!("\"02{}\"", "00".repeat(31)));
+        let back: CompressedEdwardsY = serde_json::from_str(&json).unwrap();
+        assert!(back == compressed);
+
+        let de = BytesDeserializer::<Error>::new(&two);
+        assert!(CompressedEdwardsY::deserialize(de).unwrap() == compressed);
+    }
+
+    #[cfg(feature = "vrf")]
+    #[test]
+    fn proof_round_trip() {
+        use crate::vrf::{ecvrf_prove, Proof};
+
+        let proof = ecvrf_prove(&[7u8; 32], b"leader");
+        let json = serde_json::to_string(&proof).unwrap();
+        assert_eq!(json.len(), 2 + 160);
+        let back: Proof = serde_json::from_str(&json).unwrap();
+        assert!(back == proof);
+
+        let short = format!("\"{}\"", "ab".repeat(79));
+        assert!(serde_json::from_str::<Proof>(&short).is_err());
+    }
+
+    #[test]
+    fn invalid_point_is_rejected() {
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        let point = RistrettoPoint::mul_base(&one);
+        let json = serde_json::to_string(&point).unwrap();
+        let back: RistrettoPoint = serde_json::from_str(&json).unwrap();
+        assert!(back == point);
+
+        // A negative field element is never a valid encoding.
+        let bad = format!("\"01{}\"", "00".repeat(31));
+        assert!(serde_json::from_str::<RistrettoPoint>(&bad).is_err());
+    }
+}
@@ -1,3 +1,9 @@
+use crate::Error;
+#[cfg(not(feature = "portable"))]
+use core::ptr;
+use core::sync::atomic::{compiler_fence, Ordering};
+
+#[cfg(all(feature = "asm", not(feature = "portable")))]
 extern "C" {
     pub fn fixed_time_eq_asm(lhsp: *mut u8, rhsp: *mut u8, count: usize)
         -> u32;
@@ -5,6 +11,7 @@ extern "C" {
 
 /// Compare two vectors using a fixed number of operations. If the two vectors
 /// are not of equal length, the function returns false immediately.
+#[cfg(all(feature = "asm", not(feature = "portable")))]
 pub fn fixed_time_eq(lhs: &[u8], rhs: &[u8]) -> bool {
     if lhs.len() != rhs.len() {
         false
@@ -12,9 +19,249 @@ pub fn fixed_time_eq(lhs: &[u8], rhs: &[u8]) -> bool {
         let count = lhs.len();
 
         unsafe {
-            let lhsp = lhs.get_unchecked(0);
-            let rhsp = rhs.get_unchecked(0);
-            fixed_time_eq_asm(*lhsp as *mut u8, *rhsp as *mut u8, count) == 0
+            let lhsp = lhs.as_ptr() as *mut u8;
+            let rhsp = rhs.as_ptr() as *mut u8;
+            fixed_time_eq_asm(lhsp, rhsp, count) == 0
         }
     }
 }
+
+/// Compare two vectors using a fixed number of operations. If the two vectors
+/// are not of equal length, the function returns false immediately.
+#[cfg(any(not(feature = "asm"), feature = "portable"))]
+pub fn fixed_time_eq(lhs: &[u8], rhs: &[u8]) -> bool {
+    if lhs.len() != rhs.len() {
+        false
+    } else {
+        lhs.iter().zip(rhs).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+    }
+}
+
+/// Check whether every byte is zero using a fixed number of operations,
+/// without comparing against a buffer of zeros.
+pub fn ct_is_zero(bytes: &[u8]) -> bool {
+    bytes.iter().fold(0u8, |acc, b| acc | b) == 0
+}
+
+/// Overwrite `buf` with default values (zeros) using volatile writes, so
+/// the compiler can't drop the stores even when `buf` is never read again.
+#[cfg(not(feature = "portable"))]
+pub fn zeroize<T: Copy + Default>(buf: &mut [T]) {
+    for x in buf.iter_mut() {
+        // `x` is a valid, aligned, exclusive reference.
+        unsafe { ptr::write_volatile(x, T::default()) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Overwrite `buf` with default values (zeros) without `unsafe`.
+///
+/// Volatile writes need `unsafe`, so the portable build passes the buffer
+/// through `black_box` instead, which keeps the stores from being dead.
+/// That's an optimization barrier rather than a guarantee.
+#[cfg(feature = "portable")]
+pub fn zeroize<T: Copy + Default>(buf: &mut [T]) {
+    for x in buf.iter_mut() {
+        *x = T::default();
+    }
+    core::hint::black_box(&mut *buf);
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// `zeroize` with the `zeroize` feature and nothing without it, for
+/// working values that only hardened builds pay to clear.
+#[inline(always)]
+pub(crate) fn zeroize_hardened<T: Copy + Default>(buf: &mut [T]) {
+    #[cfg(feature = "zeroize")]
+    zeroize(buf);
+    #[cfg(not(feature = "zeroize"))]
+    let _ = buf;
+}
+
+fn hex_digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+// Decodes exactly 2 * N hex digits, in either case, into N bytes.
+pub(crate) fn from_hex<const N: usize>(s: &str) -> Result<[u8; N], Error> {
+    if s.len() != 2 * N {
+        return Err(Error::InvalidLength);
+    }
+    let mut bytes = [0u8; N];
+    for (b, pair) in bytes.iter_mut().zip(s.as_bytes().chunks(2)) {
+        match (hex_digit(pair[0]), hex_digit(pair[1])) {
+            (Some(hi), Some(lo)) => *b = (hi << 4) | lo,
+            _ => return Err(Error::InvalidEncoding),
+        }
+    }
+    Ok(bytes)
+}
+
+// Implements `LowerHex`, `UpperHex`, `Display` and `Debug` for a newtype
+// around a byte array, printing every byte as two hex digits. `Display` is
+// lowercase and `Debug` wraps the hex in the type name.
+//
+// Only use it for public values; secret keys must stay unprintable.
+macro_rules! impl_hex_fmt {
+    ($ty:ident) => {
+        impl core::fmt::LowerHex for $ty {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                self.0.iter().try_for_each(|b| write!(f, "{:02x}", b))
+            }
+        }
+
+        impl core::fmt::UpperHex for $ty {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                self.0.iter().try_for_each(|b| write!(f, "{:02X}", b))
+            }
+        }
+
+        impl core::fmt::Display for $ty {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                core::fmt::LowerHex::fmt(self, f)
+            }
+        }
+
+        impl core::fmt::Debug for $ty {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                write!(f, concat!(stringify!($ty), "({:x})"), self)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_hex_fmt;
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::{
+        ct_is_zero, fixed_time_eq, from_hex, zeroize, zeroize_hardened,
+    };
+    use crate::{
+        edwards::CompressedEdwardsY, Error, X25519PublicKey, X25519SecretKey,
+    };
+    use std::format;
+
+    #[test]
+    fn fixed_time_eq_equal() {
+        let a: [u8; 32] = [0x5a; 32];
+        let b: [u8; 32] = [0x5a; 32];
+        assert!(fixed_time_eq(&a, &b));
+    }
+
+    #[test]
+    fn fixed_time_eq_unequal() {
+        let a: [u8; 32] = [0x5a; 32];
+        for i in 0..32 {
+            let mut b = a;
+            b[i] ^= 0x01;
+            assert!(!fixed_time_eq(&a, &b));
+        }
+    }
+
+    #[test]
+    fn fixed_time_eq_length_mismatch() {
+        let a: [u8; 32] = [0; 32];
+        assert!(!fixed_time_eq(&a, &a[..31]));
+    }
+
+    #[test]
+    fn zeroize_clears() {
+        let mut digits = [-8i8, 7, 1, -1];
+        zeroize(&mut digits);
+        assert_eq!(digits, [0; 4]);
+    }
+
+    #[test]
+    fn zeroize_hardened_follows_feature() {
+        let mut bits = [1i32, 0, 1];
+        zeroize_hardened(&mut bits);
+        let expected = if cfg!(feature = "zeroize") {
+            [0; 3]
+        } else {
+            [1, 0, 1]
+        };
+        assert_eq!(bits, expected);
+    }
+
+    #[test]
+    fn ct_is_zero_all_zero() {
+        assert!(ct_is_zero(&[0; 32]));
+        assert!(ct_is_zero(&[]));
+    }
+
+    #[test]
+    fn ct_is_zero_single_nonzero_byte() {
+        for i in 0..32 {
+            for &bit in &[0x01, 0x80] {
+                let mut a = [0u8; 32];
+                a[i] = bit;
+                assert!(!ct_is_zero(&a));
+            }
+        }
+    }
+
+    #[test]
+    fn hex_fmt_round_trips() {
+        let mut bytes = [0u8; 32];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = (i as u8).wrapping_mul(37);
+        }
+
+        let pk = X25519PublicKey::from_bytes(bytes);
+        assert_eq!(from_hex(&format!("{}", pk)), Ok(bytes));
+        assert_eq!(format!("{}", pk), format!("{:x}", pk));
+        assert_eq!(format!("{:X}", pk), format!("{:x}", pk).to_uppercase());
+        assert_eq!(format!("{:?}", pk), format!("X25519PublicKey({})", pk));
+
+        let point = CompressedEdwardsY::from(bytes);
+        assert_eq!(from_hex(&format!("{}", point)), Ok(bytes));
+        assert_eq!(from_hex(&format!("{:X}", point)), Ok(bytes));
+    }
+
+    #[test]
+    fn from_hex_round_trips() {
+        let mut bytes = [0u8; 32];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = (i as u8).wrapping_mul(59);
+        }
+
+        let pk = X25519PublicKey::from_bytes(bytes);
+        let parsed = X25519PublicKey::from_hex(&format!("{}", pk)).unwrap();
+        assert_eq!(parsed.as_bytes(), &bytes);
+        let parsed = X25519PublicKey::from_hex(&format!("{:X}", pk)).unwrap();
+        assert_eq!(parsed.as_bytes(), &bytes);
+
+        let point = CompressedEdwardsY::from(bytes);
+        let hex = format!("{}", point);
+        assert_eq!(CompressedEdwardsY::from_hex(&hex), Ok(point));
+        let sk = X25519SecretKey::from_hex(&hex).unwrap();
+        assert_eq!(sk.as_bytes(), &bytes);
+    }
+
+    #[test]
+    fn from_hex_rejects_bad_input() {
+        let hex = "00".repeat(32);
+        assert_eq!(from_hex::<32>(&hex[..63]), Err(Error::InvalidLength));
+        assert_eq!(from_hex::<32>(&hex[..62]), Err(Error::InvalidLength));
+        assert_eq!(
+            from_hex::<32>(&format!("{}0", hex)),
+            Err(Error::InvalidLength)
+        );
+        assert_eq!(
+            from_hex::<32>(&format!("{}g0", &hex[..62])),
+            Err(Error::InvalidEncoding)
+        );
+        assert_eq!(
+            from_hex::<32>(&format!(" {}", &hex[..63])),
+            Err(Error::InvalidEncoding)
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! ECVRF-EDWARDS25519-SHA512-TAI, the verifiable random function of
+//! [RFC 9381].
+//!
+//! The holder of a secret key can compute a 64-byte output `beta` for any
+//! input `alpha`, together with a proof that lets anyone holding the public
+//! key check that `beta` is the unique output for `alpha` under that key.
+//!
+//! Keys are Ed25519 keys: the secret key is a 32-byte seed and the public
+//! key is the encoded point [`ecvrf_public_key`] returns.
+//!
+//! [RFC 9381]: https://www.rfc-editor.org/rfc/rfc9381
+
+use crate::{
+    clamp_scalar, ed25519_scalarmult_base_compressed,
+    hazmat::{ge_scalarmult_base, ge_scalarmult_ct, GeP3},
+    multiscalar_mul, scalar_muladd, scalar_reduce, Scalar,
+};
+use sha2::{Digest, Sha512};
+
+const SUITE: u8 = 0x03;
+
+/// An 80-byte ECVRF proof: the point `Gamma`, the 16-byte challenge `c`
+/// and the scalar `s`. Compares and hashes as its bytes.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Proof([u8; 80]);
+
+impl Proof {
+    /// Wraps 80 bytes as a proof, without checking them.
+    pub fn from_bytes(bytes: [u8; 80]) -> Proof { Proof(bytes) }
+
+    /// A reference to the 80 bytes of the proof.
+    pub fn as_bytes(&self) -> &[u8; 80] { &self.0 }
+
+    /// The 80 bytes of the proof.
+    pub fn to_bytes(&self) -> [u8; 80] { self.0 }
+
+    /// The VRF output `beta` of this proof, without checking it.
+    ///
+    /// Only use the output of a proof that [`ecvrf_verify`] accepted, or
+    /// one you produced with [`ecvrf_prove`]. Returns `None` if `Gamma`
+    /// isn't a valid point encoding.
+    pub fn to_hash(&self) -> Option<[u8; 64]> {
+        let gamma = GeP3::from_bytes_strict(&self.gamma())?;
+        Some(gamma_to_hash(&gamma))
+    }
+
+    fn gamma(&self) -> [u8; 32] {
+        let mut gamma = [0u8; 32];
+        gamma.copy_from_slice(&self.0[..32]);
+        gamma
+    }
+}
+
+// Ed25519 key expansion, RFC 8032 section 5.1.5: the clamped scalar and
+// the nonce prefix.
+fn expand_secret(secret: &[u8; 32]) -> ([u8; 32], [u8; 32]) {
+    let h = Sha512::digest(secret);
+    let mut x = [0u8; 32];
+    let mut prefix = [0u8; 32];
+    x.copy_from_slice(&h[..32]);
+    prefix.copy_from_slice(&h[32..]);
+    (clamp_scalar(x), prefix)
+}
+
+fn mul_by_cofactor(point: &GeP3) -> GeP3 { point.double_repeatedly(3) }
+
+// ECVRF_encode_to_curve_try_and_increment, RFC 9381 section 5.4.1.1.
+fn encode_to_curve(public: &[u8; 32], alpha: &[u8]) -> GeP3 {
+    let mut ctr = 0u8;
+    loop {
+        let h = Sha512::new()
+            .chain_update([SUITE, 0x01])
+            .chain_update(public)
+            .chain_update(alpha)
+            .chain_update([ctr, 0x00])
+            .finalize();
+        let mut candidate = [0u8; 32];
+        candidate.copy_from_slice(&h[..32]);
+        if let Some(point) = GeP3::from_bytes_strict(&candidate) {
+            return mul_by_cofactor(&point);
+        }
+        // Half of all strings decode, so running out of counters has
+        // probability 2^-256.
+        ctr = ctr.checked_add(1).expect("no valid point found");
+    }
+}
+
+// ECVRF_challenge_generation, RFC 9381 section 5.4.3: the first 16 bytes
+// of the hash, zero-extended to a scalar.
+fn challenge(points: &[[u8; 32]; 5]) -> [u8; 32] {
+    let mut hasher = Sha512::new();
+    hasher.update([SUITE, 0x02]);
+    for point in points.iter() {
+        hasher.update(point);
+    }
+    hasher.update([0x00]);
+    let h = hasher.finalize();
+
+    let mut c = [0u8; 32];
+    c[..16].copy_from_slice(&h[..16]);
+    c
+}
+
+// ECVRF_proof_to_hash, RFC 9381 section 5.2.
+fn gamma_to_hash(gamma: &GeP3) -> [u8; 64] {
+    let h = Sha512::new()
+        .chain_update([SUITE, 0x03])
+        .chain_update(mul_by_cofactor(gamma).to_bytes())
+        .chain_update([0x00])
+        .finalize();
+    let mut beta = [0u8; 64];
+    beta.copy_from_slice(&h);
+    beta
+}
+
+/// The public key matching `secret`, the same as its Ed25519 public key.
+pub fn ecvrf_public_key(secret: &[u8; 32]) -> [u8; 32] {
+    let (x, _) = expand_secret(secret);
+    ed25519_scalarmult_base_compressed(&x)
+}
+
+/// Proves the VRF output for `alpha` under `secret`.
+///
+/// Runs in constant time with respect to the secret key. The output
+/// itself is [`Proof::to_hash`].
+pub fn ecvrf_prove(secret: &[u8; 32], alpha: &[u8]) -> Proof {
+    let (x, prefix) = expand_secret(secret);
+    let public = ge_scalarmult_base(&x).to_bytes();
+
+    let h = encode_to_curve(&public, alpha);
+    let h_bytes = h.to_bytes();
+    let gamma = ge_scalarmult_ct(&x, &h).to_bytes();
+
+    // ECVRF_nonce_generation_RFC8032, RFC 9381 section 5.4.2.2.
+    let mut wide = [0u8; 64];
+    wide.copy_from_slice(
+        &Sha512::new()
+            .chain_update(prefix)
+            .chain_update(h_bytes)
+            .finalize(),
+    );
+    let k = scalar_reduce(wide);
+
+    let k_b = ge_scalarmult_base(&k).to_bytes();
+    let k_h = ge_scalarmult_ct(&k, &h).to_bytes();
+    let c = challenge(&[public, h_bytes, gamma, k_b, k_h]);
+    let s = scalar_muladd(&c, &x, &k);
+
+    let mut pi = [0u8; 80];
+    pi[..32].copy_from_slice(&gamma);
+    pi[32..48].copy_from_slice(&c[..16]);
+    pi[48..].copy_from_slice(&s);
+    Proof(pi)
+}
+
+/// Verifies `proof` for `alpha` under `public` and returns the VRF output.
+///
+/// Returns `None` if the proof is invalid, or if `public` isn't a valid
+/// point or has small order, as RFC 9381 requires when the key isn't
+/// known to be well-formed. Not constant time; nothing here is secret.
+pub fn ecvrf_verify(
+    public: &[u8; 32],
+    alpha: &[u8],
+    proof: &Proof,
+) -> Option<[u8; 64]> {
+    let y = GeP3::from_bytes_strict(public)?;
+    if y.is_small_order() {
+        return None;
+    }
+
+    let gamma_bytes = proof.gamma();
+    let gamma = GeP3::from_bytes_strict(&gamma_bytes)?;
+    let mut c = [0u8; 32];
+    c[..16].copy_from_slice(&proof.0[32..48]);
+    let mut s = [0u8; 32];
+    s.copy_from_slice(&proof.0[48..]);
+    if !Scalar::is_canonical(&s) {
+        return None;
+    }
+
+    let h = encode_to_curve(public, alpha);
+    let h_bytes = h.to_bytes();
+
+    // U = s B - c Y and V = s H - c Gamma.
+    let u = GeP3::vartime_double_scalarmult_base(&s, &y, &c).to_bytes();
+    let v = multiscalar_mul(&[s, c], &[h, gamma.neg()]).to_bytes();
+
+    if challenge(&[*public, h_bytes, gamma_bytes, u, v]) != c {
+        return None;
+    }
+    Some(gamma_to_hash(&gamma))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ecvrf_prove, ecvrf_public_key, ecvrf_verify, Proof};
+    use crate::util::from_hex;
+
+    // RFC 9381, B.3, examples 16 to 18: (SK, alpha, PK, pi, beta).
+    static VECTORS: [(&str, &[u8], &str, &str, &str); 3] = [
+        (
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            b"",
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            "8657106690b5526245a92b003bb079ccd1a92130477671f6fc01ad16f26f723f\
+             26f8a57ccaed74ee1b190bed1f479d9727d2d0f9b005a6e456a35d4fb0daab12\
+             68a1b0db10836d9826a528ca76567805",
+            "90cf1df3b703cce59e2a35b925d411164068269d7b2d29f3301c03dd757876ff\
+             66b71dda49d2de59d03450451af026798e8f81cd2e333de5cdf4f3e140fdd8ae",
+        ),
+        (
+            "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            &[0x72],
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            "f3141cd382dc42909d19ec5110469e4feae18300e94f304590abdced48aed593\
+             3bf0864a62558b3ed7f2fea45c92a465301b3bbf5e3e54ddf2d935be3b67926d\
+             a3ef39226bbc355bdc9850112c8f4b02",
+            "eb4440665d3891d668e7e0fcaf587f1b4bd7fbfe99d0eb2211ccec90496310eb\
+             5e33821bc613efb94db5e5b54c70a848a0bef4553a41befc57663b56373a5031",
+        ),
+        (
+            "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+            &[0xaf, 0x82],
+            "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            "9bc0f79119cc5604bf02d23b4caede71393cedfbb191434dd016d30177ccbf80\
+             96bb474e53895c362d8628ee9f9ea3c0e52c7a5c691b6c18c9979866568add7a\
+             2d41b00b05081ed0f58ee5e31b3a970e",
+            "645427e5d00c62a23fb703732fa5d892940935942101e456ecca7bb217c61c45\
+             2118fec1219202a0edcf038bb6373241578be7217ba85a2687f7a0310b2df19f",
+        ),
+    ];
+
+    #[test]
+    fn rfc9381_vectors() {
+        for &(sk, alpha, pk, pi, beta) in VECTORS.iter() {
+            let sk = from_hex(sk).unwrap();
+            let pk = from_hex(pk).unwrap();
+            let beta: [u8; 64] = from_hex(beta).unwrap();
+
+            assert_eq!(ecvrf_public_key(&sk), pk);
+            let proof = ecvrf_prove(&sk, alpha);
+            assert_eq!(Ok(proof.to_bytes()), from_hex(pi));
+            assert_eq!(proof.to_hash(), Some(beta));
+            assert_eq!(ecvrf_verify(&pk, alpha, &proof), Some(beta));
+        }
+    }
+
+    #[test]
+    fn tampered_proofs_are_rejected() {
+        let sk = [7u8; 32];
+        let pk = ecvrf_public_key(&sk);
+        let proof = ecvrf_prove(&sk, b"leader");
+        assert!(ecvrf_verify(&pk, b"leader", &proof).is_some());
+        assert!(ecvrf_verify(&pk, b"follower", &proof).is_none());
+
+        for &i in [0, 40, 79].iter() {
+            let mut bytes = proof.to_bytes();
+            bytes[i] ^= 1;
+            let bad = Proof::from_bytes(bytes);
+            assert!(ecvrf_verify(&pk, b"leader", &bad).is_none());
+        }
+
+        let other = ecvrf_public_key(&[8u8; 32]);
+        assert!(ecvrf_verify(&other, b"leader", &proof).is_none());
+    }
+
+    #[test]
+    fn small_order_key_is_rejected() {
+        let proof = ecvrf_prove(&[7u8; 32], b"leader");
+        let mut identity = [0u8; 32];
+        identity[0] = 1;
+        assert!(ecvrf_verify(&identity, b"leader", &proof).is_none());
+    }
+}
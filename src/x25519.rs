@@ -0,0 +1,186 @@
+use crate::{
+    curve25519, curve25519_pk,
+    util::{from_hex, impl_hex_fmt},
+    Error,
+};
+use core::convert::TryFrom;
+
+/// A 32-byte X25519 secret key.
+///
+/// This type deliberately implements neither `Debug` nor `Display`, so a
+/// secret key can't end up in a log by accident, nor `Hash`, so it doesn't
+/// end up as a map key.
+#[derive(Clone)]
+pub struct X25519SecretKey([u8; 32]);
+
+/// A 32-byte X25519 public key (a Montgomery u-coordinate).
+///
+/// Formats as 64 hex digits with `{}`, `{:x}` and `{:X}`. Compares and
+/// hashes as its bytes, so it can key a `HashMap`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct X25519PublicKey([u8; 32]);
+
+impl_hex_fmt!(X25519PublicKey);
+
+/// A 32-byte secret shared by the two sides of an X25519 key agreement.
+#[derive(Clone)]
+pub struct X25519SharedSecret([u8; 32]);
+
+impl X25519SecretKey {
+    /// Wrap 32 secret bytes, e.g. the output of `curve25519_sk`.
+    ///
+    /// `curve25519` clamps the bytes whenever the key is used, so raw random
+    /// bytes are accepted as well.
+    pub fn from_bytes(bytes: [u8; 32]) -> X25519SecretKey {
+        X25519SecretKey(bytes)
+    }
+
+    /// Parse 64 hex digits, in either case.
+    ///
+    /// Fails with `Error::InvalidLength` for any other length and with
+    /// `Error::InvalidEncoding` for a non-hex character.
+    pub fn from_hex(s: &str) -> Result<X25519SecretKey, Error> {
+        from_hex(s).map(X25519SecretKey)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] { &self.0 }
+
+    /// Compute the public key matching this secret key.
+    pub fn public_key(&self) -> X25519PublicKey {
+        X25519PublicKey(curve25519_pk(self.0))
+    }
+
+    /// Compute the secret shared with the owner of `their_pk`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use self::curve25519::X25519SecretKey;
+    ///
+    /// let alice = X25519SecretKey::from_bytes([1; 32]); // Use random bytes!
+    /// let bob = X25519SecretKey::from_bytes([2; 32]); // Use random bytes!
+    ///
+    /// let alice_shared = alice.diffie_hellman(&bob.public_key());
+    /// let bob_shared = bob.diffie_hellman(&alice.public_key());
+    /// assert_eq!(alice_shared.as_bytes(), bob_shared.as_bytes());
+    /// ```
+    pub fn diffie_hellman(
+        &self,
+        their_pk: &X25519PublicKey,
+    ) -> X25519SharedSecret {
+        X25519SharedSecret(curve25519(self.0, their_pk.0))
+    }
+}
+
+impl X25519PublicKey {
+    pub fn from_bytes(bytes: [u8; 32]) -> X25519PublicKey {
+        X25519PublicKey(bytes)
+    }
+
+    /// Parse 64 hex digits, in either case, e.g. the output of `Display`.
+    ///
+    /// Fails with `Error::InvalidLength` for any other length and with
+    /// `Error::InvalidEncoding` for a non-hex character.
+    pub fn from_hex(s: &str) -> Result<X25519PublicKey, Error> {
+        from_hex(s).map(X25519PublicKey)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] { &self.0 }
+}
+
+impl TryFrom<&[u8]> for X25519SecretKey {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<X25519SecretKey, Error> {
+        bytes_32(bytes).map(X25519SecretKey)
+    }
+}
+
+impl TryFrom<&[u8]> for X25519PublicKey {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<X25519PublicKey, Error> {
+        bytes_32(bytes).map(X25519PublicKey)
+    }
+}
+
+impl X25519SharedSecret {
+    pub fn from_bytes(bytes: [u8; 32]) -> X25519SharedSecret {
+        X25519SharedSecret(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] { &self.0 }
+}
+
+fn bytes_32(bytes: &[u8]) -> Result<[u8; 32], Error> {
+    if bytes.len() != 32 {
+        return Err(Error::InvalidLength);
+    }
+    let mut array = [0u8; 32];
+    array.copy_from_slice(bytes);
+    Ok(array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{X25519PublicKey, X25519SecretKey};
+    use crate::{curve25519, curve25519_pk, curve25519_sk, Error};
+    use core::convert::TryFrom;
+
+    #[test]
+    fn public_keys_hash_as_bytes() {
+        extern crate std;
+        use std::collections::HashSet;
+
+        let pk = X25519SecretKey::from_bytes([1; 32]).public_key();
+        let same = X25519PublicKey::from_bytes(*pk.as_bytes());
+        let other = X25519SecretKey::from_bytes([2; 32]).public_key();
+        assert!(pk == same);
+        assert!(pk != other);
+
+        let mut peers = HashSet::new();
+        peers.insert(pk);
+        assert!(peers.contains(&same));
+        assert!(!peers.contains(&other));
+    }
+
+    #[test]
+    fn public_key_matches_curve25519_pk() {
+        let bytes = [0x42; 32];
+        let sk = X25519SecretKey::from_bytes(bytes);
+        let pk = curve25519_pk(curve25519_sk(Some(bytes)).unwrap());
+        assert_eq!(sk.public_key().as_bytes(), &pk);
+    }
+
+    #[test]
+    fn diffie_hellman_matches_curve25519() {
+        let alice = X25519SecretKey::from_bytes([0x11; 32]);
+        let bob = X25519SecretKey::from_bytes([0x22; 32]);
+        let bob_pk = X25519PublicKey::from_bytes(*bob.public_key().as_bytes());
+
+        let shared = alice.diffie_hellman(&bob_pk);
+        let expected = curve25519(
+            curve25519_sk(Some([0x11; 32])).unwrap(),
+            *bob_pk.as_bytes(),
+        );
+        assert_eq!(shared.as_bytes(), &expected);
+        assert_eq!(
+            shared.as_bytes(),
+            bob.diffie_hellman(&alice.public_key()).as_bytes()
+        );
+    }
+
+    #[test]
+    fn try_from_slice() {
+        let bytes = [0x42u8; 33];
+        let pk = X25519PublicKey::try_from(&bytes[..32]).unwrap();
+        assert_eq!(pk.as_bytes(), &[0x42; 32]);
+        assert!(X25519SecretKey::try_from(&bytes[..32]).is_ok());
+
+        assert_eq!(
+            X25519PublicKey::try_from(&bytes[..31]).err(),
+            Some(Error::InvalidLength)
+        );
+        assert!(X25519SecretKey::try_from(&bytes[..]).is_err());
+    }
+}